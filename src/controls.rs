@@ -0,0 +1,435 @@
+use vb_rt::sys::hardware;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    Fill,
+    Cross,
+    Dot,
+    Pause,
+    JumpUp,
+    JumpDown,
+    JumpLeft,
+    JumpRight,
+    /// Shows or hides the best run's cursor.
+    Ghost,
+    /// Held with Up or Down to zoom the board in or out, so a stray press never changes the zoom.
+    Zoom,
+    PrevPage,
+    NextPage,
+    /// Shows a solved puzzle's illustration from the menu.
+    ViewArt,
+    /// Cycles the menu's filter when pressed alone. Held with a direction, it sorts the menu or
+    /// opens the statistics and records.
+    Filter,
+}
+impl Action {
+    pub const ALL: [Action; 18] = [
+        Self::Up,
+        Self::Down,
+        Self::Left,
+        Self::Right,
+        Self::Fill,
+        Self::Cross,
+        Self::Dot,
+        Self::Pause,
+        Self::JumpUp,
+        Self::JumpDown,
+        Self::JumpLeft,
+        Self::JumpRight,
+        Self::Ghost,
+        Self::Zoom,
+        Self::PrevPage,
+        Self::NextPage,
+        Self::ViewArt,
+        Self::Filter,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Up => "Up",
+            Self::Down => "Down",
+            Self::Left => "Left",
+            Self::Right => "Right",
+            Self::Fill => "Fill",
+            Self::Cross => "Cross",
+            Self::Dot => "Dot",
+            Self::Pause => "Pause",
+            Self::JumpUp => "Jump Up",
+            Self::JumpDown => "Jump Down",
+            Self::JumpLeft => "Jump Left",
+            Self::JumpRight => "Jump Right",
+            Self::Ghost => "Ghost",
+            Self::Zoom => "Zoom",
+            Self::PrevPage => "Prev Page",
+            Self::NextPage => "Next Page",
+            Self::ViewArt => "View Art",
+            Self::Filter => "Filter",
+        }
+    }
+
+    fn scope(self) -> Scope {
+        match self {
+            Self::Up
+            | Self::Down
+            | Self::Left
+            | Self::Right
+            | Self::Fill
+            | Self::Cross
+            | Self::Pause => Scope::Everywhere,
+            Self::Dot
+            | Self::JumpUp
+            | Self::JumpDown
+            | Self::JumpLeft
+            | Self::JumpRight
+            | Self::Ghost
+            | Self::Zoom => Scope::Board,
+            Self::PrevPage | Self::NextPage | Self::ViewArt | Self::Filter => Scope::Menu,
+        }
+    }
+
+    /// Whether two actions can't share a button, because there's somewhere they'd both fire.
+    pub fn clashes_with(self, other: Action) -> bool {
+        let (scope, other_scope) = (self.scope(), other.scope());
+        self != other
+            && (scope == other_scope
+                || scope == Scope::Everywhere
+                || other_scope == Scope::Everywhere)
+    }
+}
+
+/// Where an action does anything. Actions for the board and for the menu can share buttons.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Scope {
+    Everywhere,
+    Board,
+    Menu,
+}
+
+/// A set of actions, read from the controller through the player's [Controls].
+#[derive(Clone, Copy, Default)]
+pub struct Actions(u32);
+impl Actions {
+    pub const NONE: Self = Self(0);
+
+    pub fn contains(self, action: Action) -> bool {
+        self.0 & (1 << action as u32) != 0
+    }
+
+    pub fn intersects(self, other: Actions) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    pub const fn with(self, action: Action) -> Self {
+        Self(self.0 | (1 << action as u32))
+    }
+
+    pub fn without(self, other: Actions) -> Self {
+        Self(self.0 & !other.0)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Button {
+    RightDown,
+    RightLeft,
+    Select,
+    Start,
+    LeftUp,
+    LeftDown,
+    LeftLeft,
+    LeftRight,
+    RightRight,
+    RightUp,
+    LeftTrigger,
+    RightTrigger,
+    B,
+    A,
+}
+impl Button {
+    pub const ALL: [Button; 14] = [
+        Self::RightDown,
+        Self::RightLeft,
+        Self::Select,
+        Self::Start,
+        Self::LeftUp,
+        Self::LeftDown,
+        Self::LeftLeft,
+        Self::LeftRight,
+        Self::RightRight,
+        Self::RightUp,
+        Self::LeftTrigger,
+        Self::RightTrigger,
+        Self::B,
+        Self::A,
+    ];
+
+    fn offset(self) -> u16 {
+        15 - self as u16
+    }
+
+    pub fn is_down(self, pad: hardware::GamePadData) -> bool {
+        (pad.into_bits() >> self.offset()) & 1 == 1
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::RightDown => "R-Down",
+            Self::RightLeft => "R-Left",
+            Self::Select => "Select",
+            Self::Start => "Start",
+            Self::LeftUp => "L-Up",
+            Self::LeftDown => "L-Down",
+            Self::LeftLeft => "L-Left",
+            Self::LeftRight => "L-Right",
+            Self::RightRight => "R-Right",
+            Self::RightUp => "R-Up",
+            Self::LeftTrigger => "L",
+            Self::RightTrigger => "R",
+            Self::B => "B",
+            Self::A => "A",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Preset {
+    Default,
+    LeftHanded,
+    SwappedAB,
+}
+impl Preset {
+    pub const ALL: [Preset; 3] = [Self::Default, Self::LeftHanded, Self::SwappedAB];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Default => "Default",
+            Self::LeftHanded => "Left-handed",
+            Self::SwappedAB => "Swap A/B",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Self::Default => Self::LeftHanded,
+            Self::LeftHanded => Self::SwappedAB,
+            Self::SwappedAB => Self::Default,
+        }
+    }
+
+    pub fn controls(self) -> Controls {
+        use Button::*;
+        let bindings = match self {
+            Self::Default => [
+                LeftUp,
+                LeftDown,
+                LeftLeft,
+                LeftRight,
+                A,
+                B,
+                RightTrigger,
+                Start,
+                RightUp,
+                RightDown,
+                RightLeft,
+                RightRight,
+                Select,
+                LeftTrigger,
+                LeftTrigger,
+                RightTrigger,
+                RightUp,
+                Select,
+            ],
+            Self::LeftHanded => [
+                RightUp,
+                RightDown,
+                RightLeft,
+                RightRight,
+                LeftTrigger,
+                RightTrigger,
+                A,
+                Start,
+                LeftUp,
+                LeftDown,
+                LeftLeft,
+                LeftRight,
+                Select,
+                B,
+                LeftLeft,
+                LeftRight,
+                LeftUp,
+                Select,
+            ],
+            Self::SwappedAB => [
+                LeftUp,
+                LeftDown,
+                LeftLeft,
+                LeftRight,
+                B,
+                A,
+                RightTrigger,
+                Start,
+                RightUp,
+                RightDown,
+                RightLeft,
+                RightRight,
+                Select,
+                LeftTrigger,
+                LeftTrigger,
+                RightTrigger,
+                RightUp,
+                Select,
+            ],
+        };
+        Controls { bindings }
+    }
+}
+
+/// Maps each [Action] to the controller button which triggers it.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Controls {
+    bindings: [Button; Action::ALL.len()],
+}
+
+impl Controls {
+    pub fn button(&self, action: Action) -> Button {
+        self.bindings[action as usize]
+    }
+
+    /// Binds a button to an action. Whatever actions clashed with it on that button take its old button,
+    /// and anything left on the old button which clashes with them comes over to the new one.
+    pub fn bind(&mut self, action: Action, button: Button) {
+        let old_button = self.button(action);
+        let displaced =
+            Action::ALL.map(|other| self.button(other) == button && other.clashes_with(action));
+        let clashes_with_displaced = |other: Action| {
+            Action::ALL
+                .into_iter()
+                .zip(displaced)
+                .any(|(moved, displaced)| displaced && moved.clashes_with(other))
+        };
+        let stayed = Action::ALL.map(|other| {
+            other != action && self.button(other) == old_button && clashes_with_displaced(other)
+        });
+        for (index, binding) in self.bindings.iter_mut().enumerate() {
+            if displaced[index] {
+                *binding = old_button;
+            } else if stayed[index] {
+                *binding = button;
+            }
+        }
+        self.bindings[action as usize] = button;
+    }
+
+    pub fn preset(&self) -> Option<Preset> {
+        Preset::ALL.into_iter().find(|p| p.controls() == *self)
+    }
+
+    /// The controller state which would trigger exactly these actions.
+    pub fn pad(&self, actions: Actions) -> hardware::GamePadData {
+        let mut bits = 0;
+        for action in Action::ALL {
+            if actions.contains(action) {
+                bits |= 1 << self.button(action).offset();
+            }
+        }
+        hardware::GamePadData::from_bits(bits)
+    }
+
+    pub fn actions(&self, pad: hardware::GamePadData) -> Actions {
+        let mut actions = Actions::default();
+        for action in Action::ALL {
+            if self.button(action).is_down(pad) {
+                actions = actions.with(action);
+            }
+        }
+        actions
+    }
+
+    pub fn to_bytes(self) -> [u8; Action::ALL.len()] {
+        self.bindings.map(|b| b as u8)
+    }
+
+    pub fn from_bytes(bytes: [u8; Action::ALL.len()]) -> Option<Self> {
+        let mut controls = Preset::Default.controls();
+        for (binding, byte) in controls.bindings.iter_mut().zip(bytes) {
+            *binding = *Button::ALL.get(byte as usize)?;
+        }
+        // every action needs a button nothing it clashes with uses
+        for (index, action) in Action::ALL.into_iter().enumerate() {
+            let clashes = Action::ALL[index + 1..].iter().any(|&other| {
+                other.clashes_with(action) && controls.button(other) == controls.button(action)
+            });
+            if clashes {
+                return None;
+            }
+        }
+        Some(controls)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum RepeatProfile {
+    Menu,
+    Board,
+}
+
+/// How often a held action fires again.
+/// Any action can repeat, not just the directions.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct KeyRepeat {
+    /// The frame on which a held action first repeats.
+    pub delay: u8,
+    /// Frames between repeats.
+    pub interval: u8,
+    /// Frames between repeats once the action has been held for a while, or 0 to never speed up.
+    pub fast_interval: u8,
+}
+
+impl KeyRepeat {
+    pub const DEFAULT: KeyRepeat = KeyRepeat {
+        delay: 10,
+        interval: 5,
+        fast_interval: 0,
+    };
+    pub const MAX_DELAY: u8 = 30;
+    pub const MAX_INTERVAL: u8 = 15;
+    const REPEATS_BEFORE_FAST: u32 = 8;
+
+    pub fn fires(&self, held_frames: u32) -> bool {
+        if held_frames == 1 {
+            return true;
+        }
+        let delay = self.delay as u32;
+        if held_frames < delay {
+            return false;
+        }
+        let since_delay = held_frames - delay;
+        let interval = self.interval as u32;
+        if self.fast_interval > 0 {
+            let fast_after = interval * Self::REPEATS_BEFORE_FAST;
+            if since_delay >= fast_after {
+                return (since_delay - fast_after) % self.fast_interval as u32 == 0;
+            }
+        }
+        since_delay % interval == 0
+    }
+
+    pub fn to_bytes(self) -> [u8; 3] {
+        [self.delay, self.interval, self.fast_interval]
+    }
+
+    pub fn from_bytes([delay, interval, fast_interval]: [u8; 3]) -> Option<Self> {
+        let valid = (2..=Self::MAX_DELAY).contains(&delay)
+            && (1..=Self::MAX_INTERVAL).contains(&interval)
+            && fast_interval < interval;
+        valid.then_some(Self {
+            delay,
+            interval,
+            fast_interval,
+        })
+    }
+}
@@ -0,0 +1,100 @@
+use vb_rt::sys::hardware;
+
+use crate::{
+    controls::{Action, Actions, Controls},
+    puzzle::Puzzle,
+};
+
+/// Frames between each input the demo makes, so it plays at a watchable pace.
+const STEP_FRAMES: u8 = 6;
+/// How often to press Fill once the puzzle is solved, to get past the win screen.
+const FINISHED_FRAMES: u16 = 100;
+
+/// Solves a puzzle for the attract mode by filling in each run of cells, left to right and top to bottom.
+pub struct Demo {
+    puzzle: &'static Puzzle,
+    cursor: (usize, usize),
+    run: Option<(usize, usize, usize)>,
+    filling: bool,
+    wait: u8,
+    finished: u16,
+}
+
+impl Demo {
+    pub fn new(puzzle: &'static Puzzle) -> Self {
+        Self {
+            puzzle,
+            cursor: (0, 0),
+            run: next_run(puzzle, 0, 0),
+            filling: false,
+            wait: STEP_FRAMES,
+            finished: 0,
+        }
+    }
+
+    pub fn next_input(&mut self, controls: &Controls) -> hardware::GamePadData {
+        controls.pad(self.next_actions())
+    }
+
+    fn next_actions(&mut self) -> Actions {
+        let held = if self.filling {
+            Actions::default().with(Action::Fill)
+        } else {
+            Actions::default()
+        };
+        if self.wait > 0 {
+            self.wait -= 1;
+            return held;
+        }
+        let Some((row, first, last)) = self.run else {
+            self.finished += 1;
+            if self.finished % FINISHED_FRAMES == 0 {
+                return Actions::default().with(Action::Fill);
+            }
+            return Actions::default();
+        };
+        self.wait = STEP_FRAMES;
+
+        if !self.filling {
+            if self.cursor.1 < row {
+                self.cursor.1 += 1;
+                return held.with(Action::Down);
+            }
+            if self.cursor.0 < first {
+                self.cursor.0 += 1;
+                return held.with(Action::Right);
+            }
+            if self.cursor.0 > first {
+                self.cursor.0 -= 1;
+                return held.with(Action::Left);
+            }
+            self.filling = true;
+            return held.with(Action::Fill);
+        }
+        if self.cursor.0 < last {
+            self.cursor.0 += 1;
+            return held.with(Action::Right);
+        }
+        self.filling = false;
+        self.run = next_run(self.puzzle, row, last + 1);
+        Actions::default()
+    }
+}
+
+/// Finds the next run of filled cells at or after the given position, as (row, first col, last col).
+fn next_run(puzzle: &Puzzle, row: usize, col: usize) -> Option<(usize, usize, usize)> {
+    let (mut row, mut col) = (row, col);
+    while row < puzzle.height {
+        let cells = &puzzle.cells[row * puzzle.width..(row + 1) * puzzle.width];
+        if let Some(first) = (col..puzzle.width).find(|&c| cells[c] == 1) {
+            let last = (first..puzzle.width)
+                .take_while(|&c| cells[c] == 1)
+                .last()
+                .unwrap_or(first);
+            return Some((row, first, last));
+        }
+        row += 1;
+        col = 0;
+    }
+    None
+}
@@ -0,0 +1,79 @@
+/// Something drawn on the board or the menu, which sits at its own depth.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Element {
+    /// The empty, crossed and dotted squares, and the grid lines around them.
+    Grid,
+    Filled,
+    Cursor,
+    /// The best run's cursor, which trails behind the player's.
+    Ghost,
+    Clues,
+    /// The clues for the cursor's row and column.
+    ActiveClues,
+    MenuItem,
+    SelectedMenuItem,
+}
+
+/// How far the game pushes things in and out of the screen.
+/// Negative parallax pulls an element toward the player, positive pushes it back.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum DepthPreset {
+    /// Everything sits on the screen. The active clues get a backdrop instead,
+    /// and the selected menu item keeps its own frame.
+    Flat,
+    Subtle,
+    Deep,
+}
+impl DepthPreset {
+    pub const ALL: [DepthPreset; 3] = [Self::Flat, Self::Subtle, Self::Deep];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Flat => "2D",
+            Self::Subtle => "Subtle",
+            Self::Deep => "Deep",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Self::Flat => Self::Subtle,
+            Self::Subtle => Self::Deep,
+            Self::Deep => Self::Flat,
+        }
+    }
+
+    pub fn parallax(self, element: Element) -> i16 {
+        match self {
+            Self::Flat => 0,
+            Self::Subtle => match element {
+                Element::Grid => 1,
+                Element::Filled => 0,
+                Element::Cursor => -1,
+                Element::Ghost => 2,
+                Element::Clues => 0,
+                Element::ActiveClues => -2,
+                Element::MenuItem => 0,
+                Element::SelectedMenuItem => -4,
+            },
+            Self::Deep => match element {
+                Element::Grid => 4,
+                Element::Filled => 1,
+                Element::Cursor => -3,
+                Element::Ghost => 6,
+                Element::Clues => -1,
+                Element::ActiveClues => -5,
+                Element::MenuItem => 2,
+                Element::SelectedMenuItem => -8,
+            },
+        }
+    }
+
+    pub fn to_byte(self) -> u8 {
+        self as u8
+    }
+
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        Self::ALL.get(byte as usize).copied()
+    }
+}
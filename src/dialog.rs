@@ -0,0 +1,93 @@
+use vb_graphics::text::TextRenderer;
+
+use crate::{
+    assets,
+    controls::{Action, RepeatProfile},
+    memory,
+    screen::{Event, Screen},
+    state::GameState,
+    transition,
+    worlds::Worlds,
+};
+
+const BG: u8 = memory::DIALOG_BG.index;
+
+/// Asks a yes or no question over whatever screen is open.
+/// "No" is picked to start with, so a stray press never confirms anything.
+pub struct ConfirmDialog {
+    yes_selected: bool,
+    on_yes: Option<Event>,
+    question_text: TextRenderer,
+    yes_text: TextRenderer,
+    no_text: TextRenderer,
+}
+
+impl ConfirmDialog {
+    pub fn new() -> Self {
+        let question_text = memory::DIALOG_QUESTION_TEXT.text_renderer();
+        question_text.render_to_bgmap(BG, (0, 0));
+        let yes_text = memory::DIALOG_YES_TEXT.text_renderer();
+        yes_text.render_to_bgmap(BG, (0, 2));
+        let no_text = memory::DIALOG_NO_TEXT.text_renderer();
+        no_text.render_to_bgmap(BG, (0, 4));
+        Self {
+            yes_selected: false,
+            on_yes: None,
+            question_text,
+            yes_text,
+            no_text,
+        }
+    }
+
+    /// Sets the question to ask next time the dialog opens, and what to do if the answer's yes.
+    pub fn ask(&mut self, question: &[u8], on_yes: Event) {
+        self.question_text.clear();
+        self.question_text.draw_text(question);
+        self.on_yes = Some(on_yes);
+    }
+
+    fn gp(&self, yes: bool) -> i16 {
+        if yes == self.yes_selected { -10 } else { -6 }
+    }
+}
+
+impl Screen for ConfirmDialog {
+    fn is_overlay(&self) -> bool {
+        true
+    }
+
+    fn init(&mut self, _state: &GameState) {
+        self.yes_selected = false;
+        // the settings menu borrows this text's characters while the dialog's closed
+        self.yes_text.clear();
+        self.yes_text.draw_text(b"Yes");
+        self.no_text.clear();
+        self.no_text.draw_text(b"No");
+    }
+
+    fn draw(&self, _state: &GameState, worlds: &mut Worlds) {
+        let text_height = assets::MENU.line_height as i16;
+        let question_width = self.question_text.width();
+        // blank out whatever's behind the dialog
+        transition::draw_cover((96, 84), (192, text_height * 2 + 16), worlds);
+        for (text, (x, y), my, parallax) in [
+            (&self.question_text, (192 - question_width / 2, 92), 0, -8),
+            (&self.yes_text, (160, 92 + text_height), 16, self.gp(true)),
+            (&self.no_text, (208, 92 + text_height), 32, self.gp(false)),
+        ] {
+            worlds.bgmap(BG, (x, y), parallax, (0, my), (text.width(), text_height));
+        }
+    }
+
+    fn update(&mut self, state: &mut GameState) -> Option<Event> {
+        let held = state.actions_repeated(RepeatProfile::Menu);
+        if held.contains(Action::Left) || held.contains(Action::Right) {
+            self.yes_selected = !self.yes_selected;
+        }
+        let pressed = state.actions_pressed();
+        if pressed.contains(Action::Fill) && self.yes_selected {
+            return self.on_yes;
+        }
+        (pressed.contains(Action::Fill) || pressed.contains(Action::Cross)).then_some(Event::Close)
+    }
+}
@@ -1,5 +1,5 @@
-mod megu;
-mod pause;
+pub mod megu;
+pub mod pause;
 
 use core::fmt::Write;
 
@@ -12,21 +12,29 @@ use vb_rt::sys::vip;
 
 use crate::{
     assets,
-    game::{
-        megu::Megu,
-        pause::{MenuItem, PauseMenu},
-    },
+    controls::{Action, Actions, RepeatProfile},
+    depth::Element,
+    game::megu::Megu,
+    illustration::{self, FADE_STEP_FRAMES, FADE_STEPS},
+    memory,
     puzzle::{EMPTY, Puzzle},
+    replay::Recording,
+    save::Progress,
+    screen::{Event, Screen},
     state::GameState,
+    worlds::Worlds,
 };
 
-#[derive(Debug, Clone, Copy)]
-enum PuzzleCell {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PuzzleCell {
     Empty,
     Cross,
     Full,
+    /// A note that a cell is probably full. The clues and the win check treat it as empty.
+    Dot,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq)]
 enum Zoom {
     One,
     Two,
@@ -38,51 +46,157 @@ impl Zoom {
             Self::Two => 16,
         }
     }
+
+    /// How big a cell is, in sixteenths of a pixel.
+    fn cell_scale(self) -> i16 {
+        self.cell_pixels() as i16 * SUBPIXELS
+    }
+
+    fn assets(self) -> GameAssets {
+        match self {
+            Self::One => GameAssets(&GAME_ASSETS_1X),
+            Self::Two => GameAssets(&GAME_ASSETS_2X),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Axis {
+    Row,
+    Column,
 }
 
 #[derive(Clone, Copy)]
 enum PuzzleState {
     Playing,
-    Paused,
     Moving,
     RevealingRow(usize),
+    /// Fading from the solved grid to the puzzle's illustration.
+    CrossFading(usize),
     ShowingText,
 }
 impl PuzzleState {
     fn numbers_visible(self) -> bool {
-        matches!(self, Self::Playing | Self::Paused)
+        matches!(self, Self::Playing)
     }
 
     fn grid_visible(self) -> bool {
-        matches!(self, Self::Playing | Self::Paused | Self::Moving)
+        matches!(self, Self::Playing | Self::Moving)
     }
 }
 
+#[derive(Clone, Copy)]
 pub enum GameResult {
     Won(u32),
     Quit,
 }
 
-const MAX_PUZZLE_SIZE: usize = 20;
+pub const MAX_PUZZLE_SIZE: usize = 20;
+const BLOCK_SIZE: usize = 5;
 const TEXT_TOP: usize = 184;
+const TEXT_BG: u8 = memory::GAME_TEXT_BG.index;
+const GRID_BG: u8 = memory::GRID_BG.index;
+const FILL_BG: u8 = memory::FILL_BG.index;
+/// The grid's drawn size eases between zooms in sixteenths of a pixel.
+const SUBPIXELS: i16 = 16;
+const ZOOM_ACTIONS: Actions = Actions::NONE.with(Action::Up).with(Action::Down);
+const STROKE_ACTIONS: Actions = Actions::NONE
+    .with(Action::Fill)
+    .with(Action::Cross)
+    .with(Action::Dot);
+
+/// Replays a best run alongside the current one, tracking only where its cursor was.
+struct Ghost {
+    state: GameState,
+    cursor: (usize, usize),
+    stroking: bool,
+    stroke_axis: Option<Axis>,
+    timer: u32,
+}
+impl Ghost {
+    fn new(recording: &Recording) -> Option<Self> {
+        let mut state = GameState::new(0);
+        state.start_playback(recording);
+        state.is_playing_back().then_some(Self {
+            state,
+            cursor: (0, 0),
+            stroking: false,
+            stroke_axis: None,
+            timer: 0,
+        })
+    }
+
+    fn restart(&mut self) {
+        self.state.restart_playback();
+        self.cursor = (0, 0);
+        self.stroking = false;
+        self.stroke_axis = None;
+        self.timer = 0;
+    }
+
+    fn catch_up(&mut self, timer: u32, size: (usize, usize)) {
+        while self.timer < timer {
+            self.state.update();
+            if !self.state.is_playing_back() {
+                return;
+            }
+            if self.state.playback_paused() {
+                // the recorded run was in the pause menu, so its inputs never reached the board
+                continue;
+            }
+            let state = &self.state;
+            let pressed = state.actions_pressed();
+            self.timer += 1;
+            let mut repeated = cursor_moves(state);
+            if self.stroking && state.settings().axis_lock {
+                repeated = lock_axis(&mut self.stroke_axis, repeated);
+            }
+            self.cursor = move_cursor(self.cursor, repeated, size);
+            self.cursor = jump_cursor(self.cursor, repeated, size);
+            if pressed.intersects(STROKE_ACTIONS) {
+                self.stroking = true;
+            }
+            if !state.actions_held().intersects(STROKE_ACTIONS) {
+                self.stroking = false;
+                self.stroke_axis = None;
+            }
+        }
+    }
+}
 
 pub struct Game {
     puzzle: &'static Puzzle,
     cells: [PuzzleCell; MAX_PUZZLE_SIZE * MAX_PUZZLE_SIZE],
     row_numbers: ArrayVec<ArrayVec<(u8, bool), MAX_PUZZLE_SIZE>, MAX_PUZZLE_SIZE>,
     col_numbers: ArrayVec<ArrayVec<(u8, bool), MAX_PUZZLE_SIZE>, MAX_PUZZLE_SIZE>,
-    puzzle_pos: (usize, usize),
+    puzzle_pos: (i16, i16),
+    view_target: (i16, i16),
     zoom: Zoom,
+    /// How big the cells are drawn, in sixteenths of a pixel, which eases toward the zoom's own size.
+    cell_scale: i16,
+    /// The cell which stays put on screen while the cells ease to a new size.
+    zoom_anchor: (usize, usize),
     cursor: (usize, usize),
     cursor_behavior: Option<PuzzleCell>,
-    cursor_delay: u8,
+    stroke_axis: Option<Axis>,
+    /// How many cells the current stroke has painted, while one is held.
+    stroke_cells: Option<usize>,
     state: PuzzleState,
     timer: u32,
+    /// Where the timer starts from, which is only ever nonzero when picking up a saved board.
+    start_time: u32,
+    /// How many cells this run has filled which aren't part of the picture.
+    mistakes: u16,
     timer_text: TextRenderer,
+    run_length: usize,
+    run_text: TextRenderer,
     name_text: BufferedTextRenderer<64>,
     source_text: BufferedTextRenderer<64>,
-    pause_menu: PauseMenu,
     megu: Megu,
+    ghost: Option<Ghost>,
+    show_ghost: bool,
+    /// Whether the board handed over to the pause menu, and hasn't picked back up yet.
+    paused: bool,
 }
 
 impl Game {
@@ -93,17 +207,27 @@ impl Game {
             row_numbers: ArrayVec::new(),
             col_numbers: ArrayVec::new(),
             puzzle_pos: (192, 112),
+            view_target: (192, 112),
             zoom: Zoom::One,
+            cell_scale: Zoom::One.cell_scale(),
+            zoom_anchor: (0, 0),
             cursor: (0, 0),
             cursor_behavior: None,
-            cursor_delay: 0,
+            stroke_axis: None,
+            stroke_cells: None,
             state: PuzzleState::Playing,
             timer: 0,
-            timer_text: TextRenderer::new(&assets::MENU, 512, (12, 2)),
-            name_text: TextRenderer::new(&assets::MENU, 536, (26, 3)).buffered(3),
-            source_text: TextRenderer::new(&assets::MENU, 614, (48, 3)).buffered(2),
-            pause_menu: PauseMenu::new(),
+            start_time: 0,
+            mistakes: 0,
+            timer_text: memory::GAME_TIMER_TEXT.text_renderer(),
+            run_length: 0,
+            run_text: memory::GAME_RUN_TEXT.text_renderer(),
+            name_text: memory::GAME_NAME_TEXT.text_renderer().buffered(3),
+            source_text: memory::GAME_SOURCE_TEXT.text_renderer().buffered(2),
             megu: Megu::new(),
+            ghost: None,
+            show_ghost: true,
+            paused: false,
         }
     }
 
@@ -112,6 +236,43 @@ impl Game {
         for cell in self.cells.iter_mut().take(puzzle.width * puzzle.height) {
             *cell = PuzzleCell::Empty;
         }
+        self.start_time = 0;
+        self.mistakes = 0;
+        self.count_clues();
+    }
+
+    /// Picks a board back up from where it was left. Has to follow [Self::load_puzzle].
+    pub fn load_progress(&mut self, progress: &Progress) {
+        let cell_count = self.puzzle.width * self.puzzle.height;
+        for (index, cell) in self.cells.iter_mut().take(cell_count).enumerate() {
+            *cell = progress.cell(index);
+        }
+        self.start_time = progress.time;
+        self.count_clues();
+    }
+
+    /// The board as it stands, to pick back up later.
+    pub fn progress(&self) -> Progress {
+        let mut progress = Progress::new(self.timer);
+        let cell_count = self.puzzle.width * self.puzzle.height;
+        for (index, &cell) in self.cells.iter().take(cell_count).enumerate() {
+            progress.set_cell(index, cell);
+        }
+        progress
+    }
+
+    /// How many cells this run has filled which aren't part of the picture. A board picked back up
+    /// only counts the ones since.
+    pub fn mistakes(&self) -> u16 {
+        self.mistakes
+    }
+
+    /// Whether this run was recorded, which only happens when it started from an empty board.
+    pub fn recorded(&self) -> bool {
+        self.start_time == 0
+    }
+
+    fn count_clues(&mut self) {
         self.row_numbers = (0..self.puzzle.height)
             .map(|row| self.row_count(row))
             .collect();
@@ -120,42 +281,14 @@ impl Game {
             .collect();
     }
 
-    pub fn init(&mut self) {
-        let (width_cells, height_cells) = self.size_cells();
-        self.zoom = if width_cells >= 24 || height_cells >= 14 {
-            Zoom::One
-        } else {
-            Zoom::Two
-        };
-        let cell_pixels = self.zoom.cell_pixels();
-        let x_offset = (384 - width_cells * cell_pixels) / 2;
-        let y_offset = (224 - height_cells * cell_pixels) / 2;
-
-        let puzzle_right = 384 - cell_pixels - x_offset;
-        let puzzle_bottom = 224 - y_offset;
-        let puzzle_left = puzzle_right - (self.puzzle.width * cell_pixels);
-        let puzzle_top = puzzle_bottom - (self.puzzle.height * cell_pixels);
-        self.puzzle_pos = (puzzle_left, puzzle_top);
-
-        self.cursor = (0, 0);
-        self.cursor_behavior = None;
-        self.cursor_delay = 0;
-        self.state = PuzzleState::Playing;
-        self.timer = 0;
-        self.timer_text.clear();
-        let _ = write!(&mut self.timer_text, "00:00:00");
-        self.timer_text.render_to_bgmap(1, (0, 0));
-        self.name_text.clear();
-        let _ = self.name_text.draw_text(self.puzzle.name);
-        self.name_text.render_to_bgmap(1, (0, 32));
-        self.source_text.clear();
-        let _ = self.source_text.draw_text(self.puzzle.source);
-        self.source_text.render_to_bgmap(1, (0, 48));
-        self.pause_menu.init();
+    pub fn set_ghost(&mut self, recording: Option<&Recording>) {
+        self.ghost = recording.and_then(Ghost::new);
+    }
 
-        let megu_mid_x = (puzzle_right as i16 + 384) / 2;
-        let megu_mid_y = (puzzle_top as i16 + puzzle_bottom as i16) / 2;
-        self.megu.init((megu_mid_x, megu_mid_y));
+    /// Starts the puzzle over from an empty board.
+    pub fn restart(&mut self, state: &GameState) {
+        self.load_puzzle(self.puzzle);
+        self.init(state);
     }
 
     pub fn size_cells(&self) -> (usize, usize) {
@@ -178,193 +311,136 @@ impl Game {
         (width, height)
     }
 
-    pub fn draw(&self) {
-        const STEREO: vip::ObjectStereo = vip::ObjectStereo::new().with_jlon(true).with_jron(true);
+    /// The zoom which shows the whole board at once.
+    fn fit_zoom(&self) -> Zoom {
+        let (width_cells, height_cells) = self.size_cells();
+        if width_cells >= 24 || height_cells >= 14 {
+            Zoom::One
+        } else {
+            Zoom::Two
+        }
+    }
 
-        let mut next_world = 31;
-        let world = vip::WORLDS.index(next_world);
-        next_world -= 1;
-        world.header().write(
-            vip::WorldHeader::new()
-                .with_bgm(vip::WorldMode::Object)
-                .with_lon(true)
-                .with_ron(true),
+    /// Switches zoom without moving the cursor on screen. The cells ease to their new size
+    /// as the view pans from there.
+    fn set_zoom(&mut self, zoom: Zoom) {
+        let (left, top) = self.grid_origin();
+        let growth = (zoom.cell_scale() - self.cell_scale) as i32;
+        let subpixels = SUBPIXELS as i32;
+        self.puzzle_pos = (
+            (left - self.cursor.0 as i32 * growth).div_euclid(subpixels) as i16,
+            (top - self.cursor.1 as i32 * growth).div_euclid(subpixels) as i16,
         );
+        self.zoom = zoom;
+        self.zoom_anchor = self.cursor;
+        self.view_target = self.pan_target(self.puzzle_pos);
+        self.render_grid();
+    }
 
-        let mut obj_index = 1023;
-        vip::SPT3.write(obj_index);
-
-        let (puzzle_left, puzzle_top) = self.puzzle_pos;
-        let (cell_pixels, game_assets) = match self.zoom {
-            Zoom::One => (8, GameAssets(&GAME_ASSETS_1X)),
-            Zoom::Two => (16, GameAssets(&GAME_ASSETS_2X)),
-        };
+    /// Where the grid's top left is drawn, in sixteenths of a pixel. While the cells ease
+    /// to a new size, the grid grows or shrinks around the zoom's anchor.
+    fn grid_origin(&self) -> (i32, i32) {
+        let growth = (self.zoom.cell_scale() - self.cell_scale) as i32;
+        let (anchor_col, anchor_row) = self.zoom_anchor;
+        (
+            self.puzzle_pos.0 as i32 * SUBPIXELS as i32 + anchor_col as i32 * growth,
+            self.puzzle_pos.1 as i32 * SUBPIXELS as i32 + anchor_row as i32 * growth,
+        )
+    }
 
-        let puzzle_right = puzzle_left + (self.puzzle.width * cell_pixels);
-        let puzzle_bottom = puzzle_top + (self.puzzle.height * cell_pixels);
+    /// Where a cell is drawn on screen.
+    fn cell_pos(&self, (col, row): (usize, usize)) -> (i16, i16) {
+        let (left, top) = self.grid_origin();
+        let scale = self.cell_scale as i32;
+        let subpixels = SUBPIXELS as i32;
+        (
+            (left + col as i32 * scale).div_euclid(subpixels) as i16,
+            (top + row as i32 * scale).div_euclid(subpixels) as i16,
+        )
+    }
 
+    /// Redraws the whole grid into its background maps, edges and all.
+    fn render_grid(&self) {
+        let game_assets = self.zoom.assets();
+        let tiles = (self.zoom.cell_pixels() / 8) as u8;
+        let (width, height) = (self.puzzle.width as u8, self.puzzle.height as u8);
         for row in 0..self.puzzle.height {
-            let revealed = match self.state {
-                PuzzleState::ShowingText => true,
-                PuzzleState::RevealingRow(revealed) => row <= (revealed / 2),
-                _ => false,
-            };
             for col in 0..self.puzzle.width {
-                let col_bright = col > 0 && col % 5 == 0;
-                let row_bright = row > 0 && row % 5 == 0;
-                if revealed {
-                    let answer = self.puzzle.cells[row * self.puzzle.width + col];
-                    if answer == 1 {
-                        let image = game_assets.square_final();
-                        let dst = (
-                            (puzzle_left + col * cell_pixels) as i16,
-                            (puzzle_top + row * cell_pixels) as i16,
-                        );
-                        obj_index = image.render_to_objects(obj_index, dst, STEREO);
-                    }
-                } else {
-                    let cell = self.cells[row * self.puzzle.width + col];
-                    let image = game_assets.square(col_bright, row_bright, cell);
-                    let dst = (
-                        (puzzle_left + col * cell_pixels) as i16,
-                        (puzzle_top + row * cell_pixels) as i16,
-                    );
-                    obj_index = image.render_to_objects(obj_index, dst, STEREO);
-                }
-            }
-            if self.state.grid_visible() {
-                let dst = (puzzle_right as i16, (puzzle_top + row * cell_pixels) as i16);
-                obj_index = game_assets
-                    .square_right()
-                    .render_to_objects(obj_index, dst, STEREO);
+                self.render_cell((col, row));
             }
         }
-        if self.state.grid_visible() {
-            for col in 0..self.puzzle.width {
-                let dst = (
-                    (puzzle_left + col * cell_pixels) as i16,
-                    puzzle_bottom as i16,
-                );
-                obj_index = game_assets
-                    .square_bottom()
-                    .render_to_objects(obj_index, dst, STEREO);
-            }
-            obj_index = game_assets.square_bottom_right().render_to_objects(
-                obj_index,
-                (puzzle_right as i16, puzzle_bottom as i16),
-                STEREO,
-            );
-        }
-
-        if self.state.numbers_visible() {
-            let cursor_x = (puzzle_left + self.cursor.0 * cell_pixels) as i16;
-            let cursor_y = (puzzle_top + self.cursor.1 * cell_pixels) as i16;
-            obj_index = game_assets.square_hover().render_to_objects(
-                obj_index,
-                (cursor_x, cursor_y),
-                STEREO,
-            );
-
-            for (row, numbers) in self.row_numbers.iter().enumerate() {
-                let mut num_x = (puzzle_left - 2 * cell_pixels) as i16;
-                let num_y = (puzzle_top + row * cell_pixels) as i16;
-                for &(num, solved) in numbers.iter().rev() {
-                    let image = if solved {
-                        game_assets.number_dim(num)
-                    } else {
-                        game_assets.number(num)
-                    };
-                    obj_index = image.render_to_objects(obj_index, (num_x, num_y), STEREO);
-                    num_x -= cell_pixels as i16;
-                }
-            }
-
-            for (col, numbers) in self.col_numbers.iter().enumerate() {
-                let num_x = (puzzle_left + col * cell_pixels) as i16;
-                let mut num_y = (puzzle_top - 2 * cell_pixels) as i16;
-                for &(num, solved) in numbers.iter().rev() {
-                    let image = if solved {
-                        game_assets.number_dim(num)
-                    } else {
-                        game_assets.number(num)
-                    };
-                    obj_index = image.render_to_objects(obj_index, (num_x, num_y), STEREO);
-                    num_y -= cell_pixels as i16;
-                }
-            }
+        for row in 0..height {
+            let dst = (width * tiles, row * tiles);
+            game_assets.square_right().render_to_bgmap(GRID_BG, dst);
+            game_assets.square_blank().render_to_bgmap(FILL_BG, dst);
         }
-
-        vip::SPT2.write(obj_index);
-
-        let world = vip::WORLDS.index(next_world);
-        next_world -= 1;
-        world.header().write(
-            vip::WorldHeader::new()
-                .with_bgm(vip::WorldMode::Normal)
-                .with_lon(true)
-                .with_ron(true)
-                .with_bg_map_base(1),
-        );
-        world.gx().write(8);
-        world.gp().write(0);
-        world.gy().write(8);
-        world.mx().write(0);
-        world.my().write(0);
-        world.w().write(self.timer_text.width() - 1);
-        world.h().write(20);
-
-        if let PuzzleState::ShowingText = self.state {
-            let world = vip::WORLDS.index(next_world);
-            next_world -= 1;
-
-            let text_width = self.name_text.final_width();
-            let text_height = assets::MENU.line_height as i16;
-
-            world.header().write(
-                vip::WorldHeader::new()
-                    .with_bgm(vip::WorldMode::Normal)
-                    .with_lon(true)
-                    .with_ron(true)
-                    .with_bg_map_base(1),
-            );
-            world.gx().write(192 - text_width / 2);
-            world.gp().write(0);
-            world.gy().write(TEXT_TOP as i16);
-            world.mx().write(0);
-            world.my().write(256);
-            world.w().write(self.name_text.width() - 1);
-            world.h().write(text_height - 1);
-
-            let world = vip::WORLDS.index(next_world);
-            next_world -= 1;
-
-            let text_width = self.source_text.final_width();
-            let text_height = assets::MENU.line_height as i16;
-
-            world.header().write(
-                vip::WorldHeader::new()
-                    .with_bgm(vip::WorldMode::Normal)
-                    .with_lon(true)
-                    .with_ron(true)
-                    .with_bg_map_base(1),
-            );
-            world.gx().write(192 - text_width / 2);
-            world.gp().write(0);
-            world.gy().write(TEXT_TOP as i16 + text_height);
-            world.mx().write(0);
-            world.my().write(384);
-            world.w().write(self.source_text.width() - 1);
-            world.h().write(text_height - 1);
+        for col in 0..width {
+            let dst = (col * tiles, height * tiles);
+            game_assets.square_bottom().render_to_bgmap(GRID_BG, dst);
+            game_assets.square_blank().render_to_bgmap(FILL_BG, dst);
         }
+        let dst = (width * tiles, height * tiles);
+        game_assets
+            .square_bottom_right()
+            .render_to_bgmap(GRID_BG, dst);
+        game_assets.square_blank().render_to_bgmap(FILL_BG, dst);
+    }
 
-        if let PuzzleState::Paused = self.state {
-            next_world = self.pause_menu.draw(next_world);
-        }
+    /// Redraws one cell into the grid's background maps.
+    fn render_cell(&self, (col, row): (usize, usize)) {
+        let game_assets = self.zoom.assets();
+        let tiles = (self.zoom.cell_pixels() / 8) as u8;
+        let dst = (col as u8 * tiles, row as u8 * tiles);
+        let col_bright = col > 0 && col % BLOCK_SIZE == 0;
+        let row_bright = row > 0 && row % BLOCK_SIZE == 0;
+        let (grid_cell, fill) = match self.cells[row * self.puzzle.width + col] {
+            PuzzleCell::Full => (
+                PuzzleCell::Empty,
+                game_assets.square(col_bright, row_bright, PuzzleCell::Full),
+            ),
+            cell => (cell, game_assets.square_blank()),
+        };
+        game_assets
+            .square(col_bright, row_bright, grid_cell)
+            .render_to_bgmap(GRID_BG, dst);
+        fill.render_to_bgmap(FILL_BG, dst);
+    }
 
-        next_world = self.megu.draw(next_world);
+    /// Where the grid should sit, given where it's heading now.
+    fn pan_target(&self, current: (i16, i16)) -> (i16, i16) {
+        let cell_pixels = self.zoom.cell_pixels() as i16;
+        let (width_cells, height_cells) = self.size_cells();
+        let (width, height) = (self.puzzle.width, self.puzzle.height);
+        (
+            pan_axis(
+                current.0,
+                (384, cell_pixels),
+                (width_cells - width, width),
+                self.cursor.0,
+                cell_pixels,
+            ),
+            pan_axis(
+                current.1,
+                (224, 0),
+                (height_cells - height, height),
+                self.cursor.1,
+                cell_pixels,
+            ),
+        )
+    }
 
-        let world = vip::WORLDS.index(next_world);
-        world.header().write(vip::WorldHeader::new().with_end(true));
+    /// Where the edges of the clues sit next to the grid. They follow the grid, until it scrolls under them.
+    fn clue_pos(&self) -> (i16, i16) {
+        let (width_cells, height_cells) = self.size_cells();
+        let scale = self.cell_scale as i32;
+        let subpixels = SUBPIXELS as i32;
+        let clue_width = (width_cells - self.puzzle.width) as i32 * scale / subpixels;
+        let clue_height = (height_cells - self.puzzle.height) as i32 * scale / subpixels;
+        let (puzzle_left, puzzle_top) = self.cell_pos((0, 0));
+        (
+            puzzle_left.max(clue_width as i16),
+            puzzle_top.max(clue_height as i16),
+        )
     }
 
     fn row_count(&self, row: usize) -> ArrayVec<(u8, bool), MAX_PUZZLE_SIZE> {
@@ -423,7 +499,7 @@ impl Game {
                         None | Some(PuzzleCell::Cross) => {
                             break;
                         }
-                        Some(PuzzleCell::Empty) => {
+                        Some(PuzzleCell::Empty | PuzzleCell::Dot) => {
                             continue 'outer;
                         }
                         Some(PuzzleCell::Full) => {
@@ -482,7 +558,9 @@ impl Game {
 
     fn is_solved(&self, mut cells: &[PuzzleCell], solution: &[u8]) -> bool {
         for count in solution {
-            while let Some((PuzzleCell::Empty | PuzzleCell::Cross, rest)) = cells.split_first() {
+            while let Some((PuzzleCell::Empty | PuzzleCell::Dot | PuzzleCell::Cross, rest)) =
+                cells.split_first()
+            {
                 cells = rest;
             }
             for _ in 0..*count {
@@ -497,53 +575,405 @@ impl Game {
         }
         cells
             .iter()
-            .all(|c| matches!(c, PuzzleCell::Empty | PuzzleCell::Cross))
+            .all(|c| matches!(c, PuzzleCell::Empty | PuzzleCell::Dot | PuzzleCell::Cross))
     }
 
-    pub fn update(&mut self, state: &mut GameState) -> Option<GameResult> {
-        self.megu.update(state);
-        if let PuzzleState::Paused = self.state {
-            match self.pause_menu.update(state) {
-                None => {}
-                Some(MenuItem::Continue) => {
-                    self.state = PuzzleState::Playing;
+    fn paint(&mut self, (col, row): (usize, usize), behavior: PuzzleCell) {
+        if !matches!(self.state, PuzzleState::Playing) {
+            return;
+        }
+        let index = row * self.puzzle.width + col;
+        // strokes only paint over empty cells, though a fill or cross can confirm a dot
+        let paintable = match (behavior, self.cells[index]) {
+            (PuzzleCell::Empty, _) | (_, PuzzleCell::Empty) => true,
+            (PuzzleCell::Full | PuzzleCell::Cross, PuzzleCell::Dot) => true,
+            _ => false,
+        };
+        if paintable && self.cells[index] != behavior {
+            self.cells[index] = behavior;
+            if behavior == PuzzleCell::Full && self.puzzle.cells[index] == 0 {
+                self.mistakes = self.mistakes.saturating_add(1);
+            }
+            if let Some(count) = &mut self.stroke_cells {
+                *count += 1;
+            }
+            self.render_cell((col, row));
+            self.col_numbers[col] = self.col_count(col);
+            self.row_numbers[row] = self.row_count(row);
+            if self.has_been_solved() {
+                // the picture is revealed whole, even if the player zoomed in
+                self.set_zoom(self.fit_zoom());
+                self.state = PuzzleState::Moving;
+                self.megu.win();
+            }
+        }
+    }
+
+    /// How many cells the current stroke has painted, or else how long the run of full cells under the cursor is.
+    fn run_length(&self) -> usize {
+        if let Some(count) = self.stroke_cells {
+            return count;
+        }
+        let (col, row) = self.cursor;
+        let width = self.puzzle.width;
+        let full =
+            |col: usize, row: usize| matches!(self.cells[row * width + col], PuzzleCell::Full);
+        if !full(col, row) {
+            return 0;
+        }
+        let left = (0..col).rev().take_while(|&c| full(c, row)).count();
+        let right = (col + 1..width).take_while(|&c| full(c, row)).count();
+        let up = (0..row).rev().take_while(|&r| full(col, r)).count();
+        let down = (row + 1..self.puzzle.height)
+            .take_while(|&r| full(col, r))
+            .count();
+        // a lone row or column of cells shows its own length, not the 1 across it
+        (left + right).max(up + down) + 1
+    }
+
+    fn display_timer(&mut self) {
+        let seconds = self.timer / 50;
+        let minutes = (seconds / 60) % 60;
+        let hours = seconds / 60 / 60;
+
+        self.timer_text.clear();
+        let _ = write!(
+            &mut self.timer_text,
+            "{:02}:{:02}:{:02}",
+            hours,
+            minutes,
+            seconds % 60
+        );
+    }
+
+    fn has_been_solved(&self) -> bool {
+        self.puzzle
+            .cells
+            .iter()
+            .zip(self.cells)
+            .all(|(solution, cell)| {
+                let expected = *solution;
+                let actual = match cell {
+                    PuzzleCell::Empty | PuzzleCell::Dot | PuzzleCell::Cross => 0,
+                    PuzzleCell::Full => 1,
+                };
+                expected == actual
+            })
+    }
+}
+
+impl Screen for Game {
+    fn init(&mut self, _state: &GameState) {
+        self.zoom = self.fit_zoom();
+        self.cell_scale = self.zoom.cell_scale();
+        self.zoom_anchor = (0, 0);
+        self.cursor = (0, 0);
+        self.view_target = self.pan_target(self.puzzle_pos);
+        self.puzzle_pos = self.view_target;
+        self.render_grid();
+
+        let cell_pixels = self.zoom.cell_pixels() as i16;
+        let (puzzle_left, puzzle_top) = self.puzzle_pos;
+        let puzzle_right = puzzle_left + self.puzzle.width as i16 * cell_pixels;
+        let puzzle_bottom = puzzle_top + self.puzzle.height as i16 * cell_pixels;
+
+        self.cursor_behavior = None;
+        self.stroke_axis = None;
+        self.stroke_cells = None;
+        self.paused = false;
+        self.state = PuzzleState::Playing;
+        self.timer = self.start_time;
+        self.display_timer();
+        self.timer_text.render_to_bgmap(TEXT_BG, (0, 0));
+        self.run_length = 0;
+        self.run_text.clear();
+        self.run_text.render_to_bgmap(TEXT_BG, (16, 0));
+        self.name_text.clear();
+        let _ = self.name_text.draw_text(self.puzzle.name);
+        self.name_text.render_to_bgmap(TEXT_BG, (0, 32));
+        self.source_text.clear();
+        let _ = self.source_text.draw_text(self.puzzle.source);
+        self.source_text.render_to_bgmap(TEXT_BG, (0, 48));
+        if let Some(ghost) = &mut self.ghost {
+            ghost.restart();
+        }
+
+        if let Some(art) = self.puzzle.illustration {
+            illustration::load(art);
+        }
+
+        let megu_mid_x = (puzzle_right + 384) / 2;
+        let megu_mid_y = (puzzle_top + puzzle_bottom) / 2;
+        self.megu.init((megu_mid_x, megu_mid_y));
+    }
+
+    fn draw(&self, state: &GameState, worlds: &mut Worlds) {
+        const STEREO: vip::ObjectStereo = vip::ObjectStereo::new().with_jlon(true).with_jron(true);
+        let settings = state.settings();
+        let depth = |element| STEREO.with_jp(settings.depth.parallax(element));
+
+        // the cells might still be easing to a new zoom, so this is the size they're drawn at
+        let cell_pixels = self.cell_scale / SUBPIXELS;
+        let game_assets = self.zoom.assets();
+        let cell_pos = |cell| self.cell_pos(cell);
+        let (puzzle_left, puzzle_top) = cell_pos((0, 0));
+
+        // the clues stay pinned to the screen edges, hiding any cells scrolled underneath them
+        let (clue_left, clue_top) = self.clue_pos();
+        let (view_left, view_top) = if self.state.numbers_visible() {
+            (clue_left, clue_top)
+        } else {
+            (-cell_pixels, -cell_pixels)
+        };
+        let visible = |(x, y): (i16, i16)| x >= view_left && y >= view_top && x < 384 && y < 224;
+
+        // while cross-fading, the solved grid dims through its palette as the illustration brightens
+        let art_step = match (self.puzzle.illustration, self.state) {
+            (Some(_), PuzzleState::CrossFading(frame)) => Some(frame / FADE_STEP_FRAMES),
+            (Some(_), PuzzleState::ShowingText) => Some(FADE_STEPS - 1),
+            _ => None,
+        };
+        // even a fully dimmed picture covers what's behind it in black, so the brighter one goes in front
+        let art_in_front = art_step.is_some_and(|step| step >= FADE_STEPS / 2);
+        let revealed_rows = match self.state {
+            PuzzleState::ShowingText if art_step.is_some() => 0,
+            PuzzleState::CrossFading(_) | PuzzleState::ShowingText => self.puzzle.height,
+            PuzzleState::RevealingRow(revealed) => revealed / 2 + 1,
+            _ => 0,
+        };
+
+        // the grid itself lives in background maps, and only the rows not yet revealed are shown
+        let edge = usize::from(self.state.grid_visible());
+        let (grid_right, grid_bottom) =
+            cell_pos((self.puzzle.width + edge, self.puzzle.height + edge));
+        let grid_left = puzzle_left.max(view_left);
+        let grid_top = cell_pos((0, revealed_rows)).1.max(view_top);
+        let grid_right = grid_right.min(384);
+        let grid_bottom = grid_bottom.min(224);
+        if grid_left < grid_right && grid_top < grid_bottom {
+            // the maps always hold the new zoom's cells, stretched to whatever size they're drawn at
+            let (drawn, rendered) = (self.cell_scale as i32, self.zoom.cell_scale() as i32);
+            let src = (
+                ((grid_left - puzzle_left) as i32 * rendered / drawn) as i16,
+                ((grid_top - puzzle_top) as i32 * rendered / drawn) as i16,
+            );
+            let size = (grid_right - grid_left, grid_bottom - grid_top);
+            let layers = [(GRID_BG, Element::Grid), (FILL_BG, Element::Filled)]
+                .map(|(bg, element)| (bg, settings.depth.parallax(element)));
+            let scale = (self.cell_scale, self.zoom.cell_scale());
+            worlds.bgmaps_scaled(&layers, (grid_left, grid_top), src, size, scale);
+        }
+
+        let art_center = (192, TEXT_TOP as i16 / 2);
+        if let (Some(art), Some(step), false) = (self.puzzle.illustration, art_step, art_in_front) {
+            illustration::draw(art, art_center, step, worlds);
+        }
+        let grid_step = match self.state {
+            PuzzleState::CrossFading(frame) => FADE_STEPS - 1 - frame / FADE_STEP_FRAMES,
+            _ => FADE_STEPS - 1,
+        };
+        illustration::fade_objects(grid_step);
+
+        worlds.objects();
+        let mut obj_index = 1023;
+        vip::SPT3.write(obj_index);
+
+        for row in 0..revealed_rows {
+            for col in 0..self.puzzle.width {
+                let dst = cell_pos((col, row));
+                let answer = self.puzzle.cells[row * self.puzzle.width + col];
+                if answer == 1 && visible(dst) {
+                    let image = game_assets.square_final();
+                    obj_index = image.render_to_objects(obj_index, dst, depth(Element::Filled));
                 }
-                Some(MenuItem::Restart) => {
-                    self.load_puzzle(self.puzzle);
-                    self.init();
-                    self.state = PuzzleState::Playing;
+            }
+        }
+
+        if self.state.numbers_visible() {
+            if settings.guide_lines {
+                for col in (0..self.puzzle.width).filter(|&c| c != self.cursor.0) {
+                    let row = self.cursor.1;
+                    let dst = cell_pos((col, row));
+                    if let PuzzleCell::Empty = self.cells[row * self.puzzle.width + col] {
+                        if visible(dst) {
+                            obj_index = game_assets.square_guide_row().render_to_objects(
+                                obj_index,
+                                dst,
+                                depth(Element::Grid),
+                            );
+                        }
+                    }
                 }
-                Some(MenuItem::Quit) => {
-                    return Some(GameResult::Quit);
+                for row in (0..self.puzzle.height).filter(|&r| r != self.cursor.1) {
+                    let col = self.cursor.0;
+                    let dst = cell_pos((col, row));
+                    if let PuzzleCell::Empty = self.cells[row * self.puzzle.width + col] {
+                        if visible(dst) {
+                            obj_index = game_assets.square_guide_col().render_to_objects(
+                                obj_index,
+                                dst,
+                                depth(Element::Grid),
+                            );
+                        }
+                    }
                 }
             }
-            if state.buttons_pressed().sta() {
-                self.state = PuzzleState::Playing;
+
+            if let Some(ghost) = self.ghost.as_ref().filter(|_| self.show_ghost) {
+                let dst = cell_pos(ghost.cursor);
+                if (self.timer / 8) % 2 == 0 && visible(dst) {
+                    obj_index = game_assets.square_hover().render_to_objects(
+                        obj_index,
+                        dst,
+                        depth(Element::Ghost),
+                    );
+                }
             }
-            return None;
+
+            obj_index = game_assets.square_hover().render_to_objects(
+                obj_index,
+                cell_pos(self.cursor),
+                depth(Element::Cursor),
+            );
+
+            // without any depth to set the active clues apart, they sit on a backdrop instead
+            let flat_clues = settings.depth.parallax(Element::ActiveClues)
+                == settings.depth.parallax(Element::Clues);
+            for (row, numbers) in self.row_numbers.iter().enumerate() {
+                let mut num_x = clue_left - 2 * cell_pixels;
+                let num_y = cell_pos((0, row)).1;
+                if num_y < clue_top || num_y >= 224 {
+                    continue;
+                }
+                let active = row == self.cursor.1;
+                let stereo = if active {
+                    depth(Element::ActiveClues)
+                } else {
+                    depth(Element::Clues)
+                };
+                for &(num, solved) in numbers.iter().rev() {
+                    if active && flat_clues {
+                        let backdrop = game_assets.clue_highlight();
+                        obj_index = backdrop.render_to_objects(obj_index, (num_x, num_y), stereo);
+                    }
+                    let image = if solved {
+                        game_assets.number_dim(num)
+                    } else {
+                        game_assets.number(num)
+                    };
+                    obj_index = image.render_to_objects(obj_index, (num_x, num_y), stereo);
+                    num_x -= cell_pixels;
+                }
+            }
+
+            for (col, numbers) in self.col_numbers.iter().enumerate() {
+                let num_x = cell_pos((col, 0)).0;
+                let mut num_y = clue_top - 2 * cell_pixels;
+                if num_x < clue_left || num_x >= 384 {
+                    continue;
+                }
+                let active = col == self.cursor.0;
+                let stereo = if active {
+                    depth(Element::ActiveClues)
+                } else {
+                    depth(Element::Clues)
+                };
+                for &(num, solved) in numbers.iter().rev() {
+                    if active && flat_clues {
+                        let backdrop = game_assets.clue_highlight();
+                        obj_index = backdrop.render_to_objects(obj_index, (num_x, num_y), stereo);
+                    }
+                    let image = if solved {
+                        game_assets.number_dim(num)
+                    } else {
+                        game_assets.number(num)
+                    };
+                    obj_index = image.render_to_objects(obj_index, (num_x, num_y), stereo);
+                    num_y -= cell_pixels;
+                }
+            }
+        }
+
+        vip::SPT2.write(obj_index);
+
+        if let (Some(art), Some(step), true) = (self.puzzle.illustration, art_step, art_in_front) {
+            illustration::draw(art, art_center, step, worlds);
+        }
+
+        let text_height = assets::MENU.line_height as i16;
+        worlds.bgmap(TEXT_BG, (8, 8), 0, (0, 0), (self.timer_text.width(), 21));
+
+        if self.state.numbers_visible() && self.run_length > 0 {
+            let size = (self.run_text.width(), text_height);
+            worlds.bgmap(TEXT_BG, (8, 8 + text_height), 0, (128, 0), size);
+        }
+
+        if let PuzzleState::ShowingText = self.state {
+            let x = 192 - self.name_text.final_width() / 2;
+            let size = (self.name_text.width(), text_height);
+            worlds.bgmap(TEXT_BG, (x, TEXT_TOP as i16), 0, (0, 256), size);
+
+            let x = 192 - self.source_text.final_width() / 2;
+            let size = (self.source_text.width(), text_height);
+            worlds.bgmap(
+                TEXT_BG,
+                (x, TEXT_TOP as i16 + text_height),
+                0,
+                (0, 384),
+                size,
+            );
         }
+
+        self.megu.draw(worlds);
+    }
+
+    fn update(&mut self, state: &mut GameState) -> Option<Event> {
+        if let (PuzzleState::Playing, 0) = (self.state, self.timer) {
+            // recording reseeds the rng, so do it before megu rolls any dice
+            state.start_recording();
+        }
+        if self.paused {
+            // only the screen on top updates, so the pause menu must have closed
+            self.paused = false;
+            state.mark_resumed();
+        }
+        self.megu.update(state);
+        self.cell_scale = approach(self.cell_scale, self.zoom.cell_scale());
         if let PuzzleState::ShowingText = self.state {
             if self.name_text.update() {
                 self.source_text.update();
             }
-            let pressed = state.buttons_pressed();
-            return (pressed.a() || pressed.sta()).then_some(GameResult::Won(self.timer));
+            let pressed = state.actions_pressed();
+            return (pressed.contains(Action::Fill) || pressed.contains(Action::Pause))
+                .then_some(Event::Finished(GameResult::Won(self.timer)));
         }
         if let PuzzleState::RevealingRow(revealed) = self.state {
             if revealed == self.puzzle.height * 2 {
-                self.state = PuzzleState::ShowingText;
+                self.state = match self.puzzle.illustration {
+                    Some(_) => PuzzleState::CrossFading(0),
+                    None => PuzzleState::ShowingText,
+                };
             } else {
                 self.state = PuzzleState::RevealingRow(revealed + 1);
             }
             return None;
         }
+        if let PuzzleState::CrossFading(frame) = self.state {
+            if frame + 1 == FADE_STEPS * FADE_STEP_FRAMES {
+                self.state = PuzzleState::ShowingText;
+            } else {
+                self.state = PuzzleState::CrossFading(frame + 1);
+            }
+            return None;
+        }
         if let PuzzleState::Moving = self.state {
-            let target_puzzle_left = (384 - self.puzzle.width * self.zoom.cell_pixels()) / 2;
-            let target_puzzle_top = (TEXT_TOP - self.puzzle.height * self.zoom.cell_pixels()) / 2;
-            if self.puzzle_pos.0 > target_puzzle_left {
-                self.puzzle_pos.0 -= 1;
-            } else if self.puzzle_pos.1 > target_puzzle_top {
-                self.puzzle_pos.1 -= 1;
+            let cell_pixels = self.zoom.cell_pixels() as i16;
+            let target_puzzle_left = (384 - self.puzzle.width as i16 * cell_pixels) / 2;
+            let target_puzzle_top = (TEXT_TOP as i16 - self.puzzle.height as i16 * cell_pixels) / 2;
+            if self.puzzle_pos.0 != target_puzzle_left {
+                self.puzzle_pos.0 += (target_puzzle_left - self.puzzle_pos.0).signum();
+            } else if self.puzzle_pos.1 != target_puzzle_top {
+                self.puzzle_pos.1 += (target_puzzle_top - self.puzzle_pos.1).signum();
             } else {
                 self.state = PuzzleState::RevealingRow(0);
             }
@@ -551,107 +981,232 @@ impl Game {
         }
         self.timer += 1;
         if self.timer % 50 == 0 {
-            let seconds = self.timer / 50;
-            let minutes = (seconds / 60) % 60;
-            let hours = seconds / 60 / 60;
-
-            self.timer_text.clear();
-            let _ = write!(
-                &mut self.timer_text,
-                "{:02}:{:02}:{:02}",
-                hours,
-                minutes,
-                seconds % 60
-            );
+            self.display_timer();
         }
 
-        let held = state.directions_held();
-        let mut cursor_moved = false;
-        let mut handle_move = |button: bool, delta: (isize, isize)| {
-            if button && self.cursor_delay == 0 {
-                let new_x = (self.cursor.0 as isize + delta.0)
-                    .rem_euclid(self.puzzle.width as isize) as usize;
-                let new_y = (self.cursor.1 as isize + delta.1)
-                    .rem_euclid(self.puzzle.height as isize) as usize;
-                if self.cursor.0 != new_x || self.cursor.1 != new_y {
-                    cursor_moved = true;
-                }
-                self.cursor = (new_x, new_y);
-            }
-        };
+        let size = (self.puzzle.width, self.puzzle.height);
+        if let Some(ghost) = &mut self.ghost {
+            ghost.catch_up(self.timer, size);
+        }
 
-        handle_move(held.ll(), (-1, 0));
-        handle_move(held.lr(), (1, 0));
-        handle_move(held.lu(), (0, -1));
-        handle_move(held.ld(), (0, 1));
+        let mut repeated = cursor_moves(state);
+        if self.cursor_behavior.is_some() && state.settings().axis_lock {
+            repeated = lock_axis(&mut self.stroke_axis, repeated);
+        }
+        self.cursor = move_cursor(self.cursor, repeated, size);
 
-        if cursor_moved {
-            self.cursor_delay = 4;
-        } else {
-            self.cursor_delay = self.cursor_delay.saturating_sub(1);
+        let from = self.cursor;
+        let to = jump_cursor(from, repeated, size);
+        if let Some(behavior) = self.cursor_behavior {
+            // dragging through a jump fills or crosses every cell along the way
+            for col in from.0.min(to.0)..=from.0.max(to.0) {
+                self.paint((col, from.1), behavior);
+            }
+            for row in from.1.min(to.1)..=from.1.max(to.1) {
+                self.paint((to.0, row), behavior);
+            }
         }
+        self.cursor = to;
+
+        let pressed = state.actions_pressed();
+        if state.actions_held().contains(Action::Zoom) {
+            let zoom = if pressed.contains(Action::Up) {
+                Zoom::Two
+            } else if pressed.contains(Action::Down) {
+                Zoom::One
+            } else {
+                self.zoom
+            };
+            if zoom != self.zoom {
+                self.set_zoom(zoom);
+            }
+        }
+        if pressed.contains(Action::Ghost) {
+            self.show_ghost = !self.show_ghost;
+        }
+        self.view_target = self.pan_target(self.view_target);
+        self.puzzle_pos = (
+            approach(self.puzzle_pos.0, self.view_target.0),
+            approach(self.puzzle_pos.1, self.view_target.1),
+        );
 
-        let pressed = state.buttons_pressed();
         let index = self.cursor.1 * self.puzzle.width + self.cursor.0;
-        if pressed.b() {
+        if pressed.contains(Action::Cross) {
             let new_cell = match self.cells[index] {
-                PuzzleCell::Empty => PuzzleCell::Cross,
+                PuzzleCell::Empty | PuzzleCell::Dot => PuzzleCell::Cross,
                 _ => PuzzleCell::Empty,
             };
             self.cursor_behavior = Some(new_cell);
         }
-        if pressed.a() {
+        if pressed.contains(Action::Fill) {
             let new_cell = match self.cells[index] {
-                PuzzleCell::Empty => PuzzleCell::Full,
+                PuzzleCell::Empty | PuzzleCell::Dot => PuzzleCell::Full,
                 _ => PuzzleCell::Empty,
             };
             self.cursor_behavior = Some(new_cell);
         }
-        let held = state.buttons_held();
-        if !held.a() && !held.b() {
+        if pressed.contains(Action::Dot) {
+            let new_cell = match self.cells[index] {
+                PuzzleCell::Empty => PuzzleCell::Dot,
+                _ => PuzzleCell::Empty,
+            };
+            self.cursor_behavior = Some(new_cell);
+        }
+        if pressed.intersects(STROKE_ACTIONS) {
+            self.stroke_cells = Some(0);
+        }
+        let held = state.actions_held();
+        if !held.intersects(STROKE_ACTIONS) {
             self.cursor_behavior = None;
+            self.stroke_axis = None;
+            self.stroke_cells = None;
         }
         if let Some(behavior) = self.cursor_behavior {
-            if matches!(behavior, PuzzleCell::Empty)
-                || matches!(self.cells[index], PuzzleCell::Empty)
-            {
-                self.cells[index] = behavior;
-                self.col_numbers[self.cursor.0] = self.col_count(self.cursor.0);
-                self.row_numbers[self.cursor.1] = self.row_count(self.cursor.1);
-                if self.has_been_solved() {
-                    self.state = PuzzleState::Moving;
-                    self.megu.win();
-                }
+            self.paint(self.cursor, behavior);
+        }
+        let run_length = self.run_length();
+        if run_length != self.run_length {
+            self.run_length = run_length;
+            self.run_text.clear();
+            if run_length > 0 {
+                let _ = write!(&mut self.run_text, "run: {}", run_length);
             }
         }
-        if pressed.sta() {
-            self.state = PuzzleState::Paused;
-            self.pause_menu.init();
+        if let PuzzleState::Moving = self.state {
+            state.stop_recording();
+        }
+        if pressed.contains(Action::Pause) {
+            self.paused = true;
+            state.mark_paused();
+            return Some(Event::Pause);
         }
         None
     }
+}
+
+/// Where the grid should start along one axis of the screen.
+/// A board which fits is centered whole. One which doesn't scrolls under its clues,
+/// pinned to the screen edge, just far enough to keep the cursor in view.
+fn pan_axis(
+    current: i16,
+    (screen, margin): (i16, i16),
+    (clue_cells, len): (usize, usize),
+    cursor: usize,
+    cell_pixels: i16,
+) -> i16 {
+    let clues = clue_cells as i16 * cell_pixels;
+    let grid = len as i16 * cell_pixels;
+    if clues + grid + margin <= screen {
+        return (screen - clues - grid - margin) / 2 + clues;
+    }
+    let view_end = screen - margin;
+    let cursor = cursor as i16 * cell_pixels;
+    let mut pos = current;
+    if pos + cursor < clues {
+        pos = clues - cursor;
+    }
+    if pos + cursor + cell_pixels > view_end {
+        pos = view_end - cell_pixels - cursor;
+    }
+    pos.clamp(view_end - grid, clues)
+}
 
-    fn has_been_solved(&self) -> bool {
-        self.puzzle
-            .cells
-            .iter()
-            .zip(self.cells)
-            .all(|(solution, cell)| {
-                let expected = *solution;
-                let actual = match cell {
-                    PuzzleCell::Empty | PuzzleCell::Cross => 0,
-                    PuzzleCell::Full => 1,
-                };
-                expected == actual
-            })
+/// Eases a position toward its target, a quarter of the way each frame.
+fn approach(pos: i16, target: i16) -> i16 {
+    let step = (target - pos) / 4;
+    if step == 0 {
+        pos + (target - pos).signum()
+    } else {
+        pos + step
+    }
+}
+
+/// How the cursor moves this frame. While Zoom is held, Up and Down zoom instead.
+fn cursor_moves(state: &GameState) -> Actions {
+    let repeated = state.actions_repeated(RepeatProfile::Board);
+    if state.actions_held().contains(Action::Zoom) {
+        repeated.without(ZOOM_ACTIONS)
+    } else {
+        repeated
+    }
+}
+
+fn move_cursor(cursor: (usize, usize), held: Actions, size: (usize, usize)) -> (usize, usize) {
+    let mut cursor = cursor;
+    let mut handle_move = |button: bool, delta: (isize, isize)| {
+        if button {
+            let new_x = (cursor.0 as isize + delta.0).rem_euclid(size.0 as isize) as usize;
+            let new_y = (cursor.1 as isize + delta.1).rem_euclid(size.1 as isize) as usize;
+            cursor = (new_x, new_y);
+        }
+    };
+
+    handle_move(held.contains(Action::Left), (-1, 0));
+    handle_move(held.contains(Action::Right), (1, 0));
+    handle_move(held.contains(Action::Up), (0, -1));
+    handle_move(held.contains(Action::Down), (0, 1));
+
+    cursor
+}
+
+/// Locks a stroke to the axis of its first movement, then drops any movement off that axis.
+fn lock_axis(axis: &mut Option<Axis>, held: Actions) -> Actions {
+    let horizontal = Actions::default()
+        .with(Action::Left)
+        .with(Action::Right)
+        .with(Action::JumpLeft)
+        .with(Action::JumpRight);
+    let vertical = Actions::default()
+        .with(Action::Up)
+        .with(Action::Down)
+        .with(Action::JumpUp)
+        .with(Action::JumpDown);
+    if axis.is_none() {
+        if held.intersects(horizontal) {
+            *axis = Some(Axis::Row);
+        } else if held.intersects(vertical) {
+            *axis = Some(Axis::Column);
+        }
+    }
+    match axis {
+        Some(Axis::Row) => held.without(vertical),
+        Some(Axis::Column) => held.without(horizontal),
+        None => held,
     }
 }
 
+/// Jumps the cursor to the next grid block boundary, stopping at the edge of the puzzle.
+fn jump_cursor(cursor: (usize, usize), held: Actions, size: (usize, usize)) -> (usize, usize) {
+    let jump = |pos: usize, len: usize, back: bool, forward: bool| {
+        if back && pos > 0 {
+            (pos - 1) / BLOCK_SIZE * BLOCK_SIZE
+        } else if forward {
+            ((pos / BLOCK_SIZE + 1) * BLOCK_SIZE).min(len - 1)
+        } else {
+            pos
+        }
+    };
+    (
+        jump(
+            cursor.0,
+            size.0,
+            held.contains(Action::JumpLeft),
+            held.contains(Action::JumpRight),
+        ),
+        jump(
+            cursor.1,
+            size.1,
+            held.contains(Action::JumpUp),
+            held.contains(Action::JumpDown),
+        ),
+    )
+}
+
 fn is_valid(mut cells: &[PuzzleCell], solution: &[u8]) -> bool {
     let Some((&count, solution)) = solution.split_first() else {
         return cells
             .iter()
-            .all(|c| matches!(c, PuzzleCell::Empty | PuzzleCell::Cross));
+            .all(|c| matches!(c, PuzzleCell::Empty | PuzzleCell::Dot | PuzzleCell::Cross));
     };
     'outer: loop {
         while let Some((PuzzleCell::Cross, rest)) = cells.split_first() {
@@ -665,7 +1220,7 @@ fn is_valid(mut cells: &[PuzzleCell], solution: &[u8]) -> bool {
             };
             cells = rest;
             match next {
-                PuzzleCell::Empty => {}
+                PuzzleCell::Empty | PuzzleCell::Dot => {}
                 PuzzleCell::Full => {
                     full_seen = true;
                 }
@@ -682,7 +1237,7 @@ fn is_valid(mut cells: &[PuzzleCell], solution: &[u8]) -> bool {
         match cells.split_first() {
             None => return solution.is_empty(),
             Some((PuzzleCell::Full, _)) => {}
-            Some((PuzzleCell::Empty, rest)) => {
+            Some((PuzzleCell::Empty | PuzzleCell::Dot, rest)) => {
                 if is_valid(rest, solution) {
                     return true;
                 }
@@ -712,7 +1267,7 @@ fn is_valid(mut cells: &[PuzzleCell], solution: &[u8]) -> bool {
     }
 }
 
-struct GameAssets(&'static [&'static Image; 57]);
+struct GameAssets(&'static [&'static Image; 65]);
 impl GameAssets {
     fn square(&self, col_bright: bool, row_bright: bool, cell: PuzzleCell) -> &'static Image {
         let index = match (col_bright, row_bright, cell) {
@@ -728,34 +1283,50 @@ impl GameAssets {
             (true, true, PuzzleCell::Empty) => 9,
             (true, true, PuzzleCell::Cross) => 10,
             (true, true, PuzzleCell::Full) => 11,
+            (false, false, PuzzleCell::Dot) => 12,
+            (false, true, PuzzleCell::Dot) => 13,
+            (true, false, PuzzleCell::Dot) => 14,
+            (true, true, PuzzleCell::Dot) => 15,
         };
         self.0[index]
     }
     fn square_right(&self) -> &'static Image {
-        self.0[12]
+        self.0[16]
     }
     fn square_bottom(&self) -> &'static Image {
-        self.0[13]
+        self.0[17]
     }
     fn square_bottom_right(&self) -> &'static Image {
-        self.0[14]
+        self.0[18]
     }
     fn square_hover(&self) -> &'static Image {
-        self.0[15]
+        self.0[19]
     }
     fn square_final(&self) -> &'static Image {
-        self.0[16]
+        self.0[20]
+    }
+    fn square_guide_row(&self) -> &'static Image {
+        self.0[21]
+    }
+    fn square_guide_col(&self) -> &'static Image {
+        self.0[22]
+    }
+    fn square_blank(&self) -> &'static Image {
+        self.0[23]
     }
 
     fn number(&self, num: u8) -> &'static Image {
-        self.0[num as usize + 16]
+        self.0[num as usize + 23]
     }
     fn number_dim(&self, num: u8) -> &'static Image {
-        self.0[num as usize + 36]
+        self.0[num as usize + 43]
+    }
+    fn clue_highlight(&self) -> &'static Image {
+        self.0[64]
     }
 }
 
-const GAME_ASSETS_1X: [&Image; 57] = [
+const GAME_ASSETS_1X: [&Image; 65] = [
     &assets::SQUARE_DD_EMPTY,
     &assets::SQUARE_DD_CROSS,
     &assets::SQUARE_DD_FULL,
@@ -768,11 +1339,18 @@ const GAME_ASSETS_1X: [&Image; 57] = [
     &assets::SQUARE_BB_EMPTY,
     &assets::SQUARE_BB_CROSS,
     &assets::SQUARE_BB_FULL,
+    &assets::SQUARE_DD_DOT,
+    &assets::SQUARE_DB_DOT,
+    &assets::SQUARE_BD_DOT,
+    &assets::SQUARE_BB_DOT,
     &assets::SQUARE_RIGHT,
     &assets::SQUARE_BOTTOM,
     &assets::SQUARE_BOTTOM_RIGHT,
     &assets::SQUARE_HOVER,
     &assets::SQUARE_FINAL,
+    &assets::SQUARE_GUIDE_ROW,
+    &assets::SQUARE_GUIDE_COL,
+    &assets::SQUARE_BLANK,
     &assets::NUMBER_1,
     &assets::NUMBER_2,
     &assets::NUMBER_3,
@@ -813,9 +1391,10 @@ const GAME_ASSETS_1X: [&Image; 57] = [
     &assets::NUMBER_18_DIM,
     &assets::NUMBER_19_DIM,
     &assets::NUMBER_20_DIM,
+    &assets::CLUE_HIGHLIGHT,
 ];
 
-const GAME_ASSETS_2X: [&Image; 57] = [
+const GAME_ASSETS_2X: [&Image; 65] = [
     &assets::SQUARE_DD_EMPTY_2X,
     &assets::SQUARE_DD_CROSS_2X,
     &assets::SQUARE_DD_FULL_2X,
@@ -828,11 +1407,18 @@ const GAME_ASSETS_2X: [&Image; 57] = [
     &assets::SQUARE_BB_EMPTY_2X,
     &assets::SQUARE_BB_CROSS_2X,
     &assets::SQUARE_BB_FULL_2X,
+    &assets::SQUARE_DD_DOT_2X,
+    &assets::SQUARE_DB_DOT_2X,
+    &assets::SQUARE_BD_DOT_2X,
+    &assets::SQUARE_BB_DOT_2X,
     &assets::SQUARE_RIGHT_2X,
     &assets::SQUARE_BOTTOM_2X,
     &assets::SQUARE_BOTTOM_RIGHT_2X,
     &assets::SQUARE_HOVER_2X,
     &assets::SQUARE_FINAL_2X,
+    &assets::SQUARE_GUIDE_ROW_2X,
+    &assets::SQUARE_GUIDE_COL_2X,
+    &assets::SQUARE_BLANK_2X,
     &assets::NUMBER_1_2X,
     &assets::NUMBER_2_2X,
     &assets::NUMBER_3_2X,
@@ -873,4 +1459,5 @@ const GAME_ASSETS_2X: [&Image; 57] = [
     &assets::NUMBER_18_DIM_2X,
     &assets::NUMBER_19_DIM_2X,
     &assets::NUMBER_20_DIM_2X,
+    &assets::CLUE_HIGHLIGHT_2X,
 ];
@@ -1,9 +1,8 @@
 use rand::Rng;
-use vb_rt::sys::vip;
 
-use crate::{assets, state::GameState};
+use crate::{assets, memory, state::GameState, worlds::Worlds};
 
-const BG: u8 = 5;
+const BG: u8 = memory::MEGU_BG.index;
 
 struct Pose {
     back_l: (i16, i16),
@@ -15,68 +14,20 @@ struct Pose {
 }
 
 impl Pose {
-    fn draw(&self, pos: (i16, i16), face_right: bool, jiggle: bool, next_world: usize) -> usize {
-        let mut next_world = next_world;
-
+    fn draw(&self, pos: (i16, i16), face_right: bool, jiggle: bool, worlds: &mut Worlds) {
         let back = if face_right { self.back_r } else { self.back_l };
-        let world = vip::WORLDS.index(next_world);
-        next_world -= 1;
-        world.header().write(
-            vip::WorldHeader::new()
-                .with_bgm(vip::WorldMode::Normal)
-                .with_lon(true)
-                .with_ron(true)
-                .with_bg_map_base(BG),
-        );
-        world.gx().write(pos.0);
-        world.gp().write(2);
-        world.gy().write(pos.1 + 16);
-        world.mx().write(back.0);
-        world.my().write(back.1);
-        world.w().write(63);
-        world.h().write(55);
+        worlds.bgmap(BG, (pos.0, pos.1 + 16), 2, back, (64, 56));
 
         let mid = if face_right { self.mid_r } else { self.mid_l };
-        let world = vip::WORLDS.index(next_world);
-        next_world -= 1;
-        world.header().write(
-            vip::WorldHeader::new()
-                .with_bgm(vip::WorldMode::Normal)
-                .with_lon(true)
-                .with_ron(true)
-                .with_bg_map_base(BG),
-        );
-        world.gx().write(pos.0);
-        world.gp().write(0);
-        world.gy().write(pos.1);
-        world.mx().write(mid.0);
-        world.my().write(mid.1);
-        world.w().write(63);
-        world.h().write(79);
+        worlds.bgmap(BG, pos, 0, mid, (64, 80));
 
         let front = if face_right {
             self.front_r
         } else {
             self.front_l
         };
-        let world = vip::WORLDS.index(next_world);
-        next_world -= 1;
-        world.header().write(
-            vip::WorldHeader::new()
-                .with_bgm(vip::WorldMode::Normal)
-                .with_lon(true)
-                .with_ron(true)
-                .with_bg_map_base(BG),
-        );
-        world.gx().write(pos.0);
-        world.gp().write(-1);
-        world.gy().write(pos.1 + if jiggle { 15 } else { 16 });
-        world.mx().write(front.0);
-        world.my().write(front.1);
-        world.w().write(63);
-        world.h().write(63);
-
-        next_world
+        let front_y = pos.1 + if jiggle { 15 } else { 16 };
+        worlds.bgmap(BG, (pos.0, front_y), -1, front, (64, 64));
     }
 }
 
@@ -162,13 +113,13 @@ impl Megu {
         self.counter = 0;
     }
 
-    pub fn draw(&self, next_world: usize) -> usize {
+    pub fn draw(&self, worlds: &mut Worlds) {
         let pose = match self.celebrate {
             Some(false) => &self.celebrating_1,
             Some(true) => &self.celebrating_2,
             None => &self.idle,
         };
-        pose.draw(self.pos, self.face_right, self.jiggle, next_world)
+        pose.draw(self.pos, self.face_right, self.jiggle, worlds);
     }
 
     pub fn update(&mut self, state: &mut GameState) {
@@ -1,10 +1,18 @@
 use vb_graphics::text::TextRenderer;
-use vb_rt::sys::vip;
 
-use crate::{assets, state::GameState};
+use crate::{
+    assets,
+    controls::{Action, RepeatProfile},
+    memory,
+    screen::{Event, Screen},
+    state::GameState,
+    worlds::Worlds,
+};
+
+const BG: u8 = memory::PAUSE_BG.index;
 
 #[derive(Clone, Copy, PartialEq, Eq)]
-pub enum MenuItem {
+enum MenuItem {
     Continue,
     Restart,
     Quit,
@@ -35,19 +43,19 @@ pub struct PauseMenu {
 
 impl PauseMenu {
     pub fn new() -> Self {
-        assets::PAUSE.render_to_bgmap(3, (0, 0));
+        assets::PAUSE.render_to_bgmap(BG, (0, 0));
 
         let active = MenuItem::Continue;
 
-        let mut continue_text = TextRenderer::new(&assets::MENU, 758, (12, 2));
+        let mut continue_text = memory::PAUSE_CONTINUE_TEXT.text_renderer();
         continue_text.draw_text(b"Continue");
-        continue_text.render_to_bgmap(3, (0, 32));
-        let mut restart_text = TextRenderer::new(&assets::MENU, 782, (12, 2));
+        continue_text.render_to_bgmap(BG, (0, 32));
+        let mut restart_text = memory::PAUSE_RESTART_TEXT.text_renderer();
         restart_text.draw_text(b"Restart");
-        restart_text.render_to_bgmap(3, (0, 34));
-        let mut quit_text = TextRenderer::new(&assets::MENU, 806, (12, 2));
+        restart_text.render_to_bgmap(BG, (0, 34));
+        let mut quit_text = memory::PAUSE_QUIT_TEXT.text_renderer();
         quit_text.draw_text(b"Quit");
-        quit_text.render_to_bgmap(3, (0, 36));
+        quit_text.render_to_bgmap(BG, (0, 36));
 
         Self {
             active,
@@ -56,29 +64,19 @@ impl PauseMenu {
             quit_text,
         }
     }
+}
+
+impl Screen for PauseMenu {
+    fn is_overlay(&self) -> bool {
+        true
+    }
 
-    pub fn init(&mut self) {
+    fn init(&mut self, _state: &GameState) {
         self.active = MenuItem::Continue;
     }
 
-    pub fn draw(&self, next_world: usize) -> usize {
-        let mut next_world = next_world;
-        let world = vip::WORLDS.index(next_world);
-        next_world -= 1;
-        world.header().write(
-            vip::WorldHeader::new()
-                .with_bgm(vip::WorldMode::Normal)
-                .with_lon(true)
-                .with_ron(true)
-                .with_bg_map_base(3),
-        );
-        world.gx().write(140);
-        world.gp().write(-4);
-        world.gy().write(78);
-        world.mx().write(0);
-        world.my().write(0);
-        world.w().write(191);
-        world.h().write(111);
+    fn draw(&self, _state: &GameState, worlds: &mut Worlds) {
+        worlds.bgmap(BG, (140, 78), -4, (0, 0), (192, 112));
 
         for (index, (item, text)) in [
             (MenuItem::Continue, &self.continue_text),
@@ -90,41 +88,29 @@ impl PauseMenu {
         {
             let x = 158;
             let y = 86 + (index as i16 * 16);
-
-            let world = vip::WORLDS.index(next_world);
-            next_world -= 1;
-            world.header().write(
-                vip::WorldHeader::new()
-                    .with_bgm(vip::WorldMode::Normal)
-                    .with_lon(true)
-                    .with_ron(true)
-                    .with_bg_map_base(3),
-            );
-            world.gx().write(x);
-            world.gp().write(if item == self.active { -6 } else { -3 });
-            world.gy().write(y);
-            world.mx().write(0);
-            world.my().write(256 + (index as i16 * 16));
-            world.w().write(text.width() - 1);
-            world.h().write(15);
+            let parallax = if item == self.active { -6 } else { -3 };
+            let src = (0, 256 + (index as i16 * 16));
+            worlds.bgmap(BG, (x, y), parallax, src, (text.width(), 16));
         }
-
-        next_world
     }
 
-    pub fn update(&mut self, state: &GameState) -> Option<MenuItem> {
-        let held = state.directions_held();
-        if held.lu() {
+    fn update(&mut self, state: &mut GameState) -> Option<Event> {
+        let held = state.actions_repeated(RepeatProfile::Menu);
+        if held.contains(Action::Up) {
             self.active = self.active.prev();
         }
-        if held.ld() {
+        if held.contains(Action::Down) {
             self.active = self.active.next();
         }
-        let pressed = state.buttons_pressed();
-        if pressed.a() {
-            Some(self.active)
-        } else if pressed.b() || pressed.sta() {
-            Some(MenuItem::Continue)
+        let pressed = state.actions_pressed();
+        if pressed.contains(Action::Fill) {
+            Some(match self.active {
+                MenuItem::Continue => Event::Close,
+                MenuItem::Restart => Event::Restart,
+                MenuItem::Quit => Event::ConfirmQuit,
+            })
+        } else if pressed.contains(Action::Cross) || pressed.contains(Action::Pause) {
+            Some(Event::Close)
         } else {
             None
         }
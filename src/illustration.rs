@@ -0,0 +1,81 @@
+use vb_graphics as gfx;
+use vb_rt::sys::vip;
+
+use crate::{
+    controls::Action,
+    memory,
+    puzzle::{ILLUSTRATION_CHAR_OFFSET, Illustration},
+    screen::{Event, Screen},
+    state::GameState,
+    worlds::Worlds,
+};
+
+const BG: u8 = memory::ILLUSTRATION_BG.index;
+pub const FADE_STEPS: usize = 4;
+/// How long each step of a fade lasts, in frames.
+pub const FADE_STEP_FRAMES: usize = 12;
+/// Palettes which brighten a picture from black up to its full four shades.
+const FADE_PALETTES: [u8; FADE_STEPS] =
+    [0b00_00_00_00, 0b01_01_00_00, 0b10_01_01_00, 0b11_10_01_00];
+
+/// Loads an illustration's characters over the menu icons, and lays it out in its background map.
+pub fn load(illustration: &Illustration) {
+    gfx::load_character_data(illustration.chardata, ILLUSTRATION_CHAR_OFFSET);
+    illustration.image.render_to_bgmap(BG, (0, 0));
+}
+
+/// Draws a loaded illustration centered on the given point, at some step of fading in.
+pub fn draw(
+    illustration: &Illustration,
+    (center_x, center_y): (i16, i16),
+    step: usize,
+    worlds: &mut Worlds,
+) {
+    vip::GPLT1.write(FADE_PALETTES[step]);
+    let width = illustration.image.width_cells as i16 * 8;
+    let height = illustration.image.height_cells as i16 * 8;
+    let pos = (center_x - width / 2, center_y - height / 2);
+    worlds.bgmap(BG, pos, 0, (0, 0), (width, height));
+}
+
+/// Dims every object on screen to some step of fading in.
+pub fn fade_objects(step: usize) {
+    vip::JPLT0.write(FADE_PALETTES[step]);
+}
+
+/// Shows a solved puzzle's illustration on its own, until any button sends the player back.
+pub struct IllustrationViewer {
+    illustration: Option<&'static Illustration>,
+}
+
+impl IllustrationViewer {
+    pub fn new() -> Self {
+        Self { illustration: None }
+    }
+
+    pub fn load_illustration(&mut self, illustration: &'static Illustration) {
+        self.illustration = Some(illustration);
+    }
+}
+
+impl Screen for IllustrationViewer {
+    fn init(&mut self, _state: &GameState) {
+        if let Some(illustration) = self.illustration {
+            load(illustration);
+        }
+    }
+
+    fn draw(&self, _state: &GameState, worlds: &mut Worlds) {
+        if let Some(illustration) = self.illustration {
+            draw(illustration, (192, 112), FADE_STEPS - 1, worlds);
+        }
+    }
+
+    fn update(&mut self, state: &mut GameState) -> Option<Event> {
+        let pressed = state.actions_pressed();
+        (pressed.contains(Action::Fill)
+            || pressed.contains(Action::Cross)
+            || pressed.contains(Action::Pause))
+        .then_some(Event::Close)
+    }
+}
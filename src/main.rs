@@ -2,17 +2,52 @@
 #![no_std]
 
 mod assets;
+mod controls;
+mod demo;
+mod depth;
+mod dialog;
 mod game;
+mod illustration;
+mod memory;
 mod menu;
+mod name_entry;
+mod pager;
 mod puzzle;
+mod records;
+mod replay;
 mod save;
+mod screen;
+mod settings;
 mod state;
+mod stats;
 mod title;
+mod transition;
+mod unlock;
+mod worlds;
 
 use vb_graphics as gfx;
-use vb_rt::sys::vip::Character;
 
-use crate::{game::Game, menu::Menu, state::GameState, title::Title};
+use rand::Rng;
+
+use crate::{
+    demo::Demo,
+    dialog::ConfirmDialog,
+    game::{Game, GameResult, pause::PauseMenu},
+    illustration::IllustrationViewer,
+    menu::Menu,
+    name_entry::NameEntry,
+    puzzle::{BONUS_START, PUZZLES},
+    records::RecordsScreen,
+    save::{self, Settings},
+    screen::{Charset, Event, Screen, ScreenId, ScreenStack},
+    settings::SettingsMenu,
+    state::GameState,
+    stats::StatsScreen,
+    title::Title,
+    transition::{FADE, IRIS, Transition, WIPE, ZOOM},
+    unlock::UnlockScreen,
+    worlds::Worlds,
+};
 
 vb_rt::rom_header!("Virtual Picross", "SG", "VPIC");
 vb_rt::main!({ main() });
@@ -27,92 +62,308 @@ fn main() {
     gfx::set_colors(32, 64, 32);
     gfx::set_bkcol(0);
 
-    let mut loaded_chardata: &[Character] = &assets::TITLE;
-    gfx::load_character_data(loaded_chardata, 0);
-    gfx::load_character_data(&puzzle::ICON_CHARS, puzzle::ICON_CHAR_OFFSET);
+    let mut state = GameState::new(save::boot_seed());
+    state.set_settings(Settings::load());
 
-    let mut state = GameState::new();
+    let mut screens = ScreenManager::new(ScreenId::Title);
+    gfx::load_character_data(&puzzle::ICON_CHARS, puzzle::ICON_CHAR_OFFSET);
+    transition::init();
 
-    let mut title = Title::new();
-    let mut menu = Menu::new();
-    let mut game = Game::new();
+    let mut watching = false;
+    let mut demo: Option<Demo> = None;
 
-    let mut active = ActiveScreen::Title;
-    let mut transition = Some(Transition::FadeIn(0));
+    let mut change = Some(ScreenChange::Entering(FADE.entering()));
+    // Whether any frame has run out of worlds. Release builds don't stop for it, so the backdrop
+    // lights up instead and stays lit, since every world is taken by then.
+    let mut dropped_draws = false;
 
     FRAME.enable_interrupts();
 
     loop {
-        match active {
-            ActiveScreen::Title => title.draw(),
-            ActiveScreen::Menu => menu.draw(),
-            ActiveScreen::Game => game.draw(),
-        };
+        let mut worlds = Worlds::new();
+        screens.draw(&state, &mut worlds);
+        if let Some(ScreenChange::Leaving(transition, _) | ScreenChange::Entering(transition)) =
+            &change
+        {
+            transition.draw(&mut worlds);
+        }
+        if worlds.finish() > 0 && !dropped_draws {
+            dropped_draws = true;
+            gfx::set_bkcol(1);
+        }
 
         FRAME.wait_for_new_frame();
 
-        state.update();
+        match &mut demo {
+            Some(demo) if change.is_none() => {
+                let pressed = demo.next_input(&state.settings().controls);
+                state.update_scripted(pressed);
+            }
+            _ => state.update(),
+        }
 
-        match &mut transition {
-            Some(Transition::FadeIn(amount)) => {
-                *amount += 1;
-                gfx::set_colors(*amount, *amount * 2, *amount);
-                if *amount == 32 {
-                    transition = None;
+        match &mut change {
+            Some(ScreenChange::Entering(entering)) => {
+                if entering.update() {
+                    change = None;
+                    if let (ScreenId::Game, true, Some(recording)) =
+                        (screens.stack.top(), watching, screens.menu.ghost())
+                    {
+                        state.start_playback(recording);
+                    }
                 }
             }
-            Some(Transition::FadeOut(amount, next)) => {
-                *amount -= 1;
-                gfx::set_colors(*amount, *amount * 2, *amount);
-                if *amount == 0 {
-                    let chardata: &[Character] = match next {
-                        ActiveScreen::Title => &assets::TITLE,
-                        _ => &assets::ALL,
-                    };
-                    if !core::ptr::addr_eq(loaded_chardata, chardata) {
-                        loaded_chardata = chardata;
-                        gfx::load_character_data(loaded_chardata, 0);
-                    }
-                    match next {
-                        ActiveScreen::Game => game.init(),
-                        ActiveScreen::Menu => menu.init(),
-                        _ => {}
-                    }
-                    active = *next;
-                    transition = Some(Transition::FadeIn(0));
+            Some(ScreenChange::Leaving(leaving, stack_change)) => {
+                if leaving.update() {
+                    let transition = *leaving;
+                    screens.change(*stack_change, Some(transition), &state);
+                    change = Some(ScreenChange::Entering(transition.entering()));
                 }
             }
-            None => match active {
-                ActiveScreen::Title => {
-                    if title.update(&state) {
-                        transition = Some(Transition::FadeOut(31, ActiveScreen::Menu));
+            None => {
+                let event = if (demo.is_some() || watching)
+                    && screens.stack.contains(ScreenId::Game)
+                    && state.live_buttons_pressed().into_bits() != 0
+                {
+                    // any button ends the demo or a replay
+                    Some(Event::Finished(GameResult::Quit))
+                } else if watching && !state.is_playing_back() {
+                    // the replay ends with its recording, rather than handing the run to the player
+                    Some(Event::Finished(GameResult::Quit))
+                } else {
+                    screens.update(&mut state)
+                };
+                match event {
+                    Some(Event::Start) => {
+                        let next = StackChange::Switch(ScreenId::Menu);
+                        change = Some(ScreenChange::Leaving(IRIS, next));
                     }
-                }
-                ActiveScreen::Menu => {
-                    if let Some(puzzle) = menu.update(&state) {
-                        game.load_puzzle(puzzle);
-                        transition = Some(Transition::FadeOut(31, ActiveScreen::Game));
+                    Some(Event::Demo) => {
+                        // the demo never gives away the bonus puzzles
+                        let puzzle = &PUZZLES[state.rand().random_range(0..BONUS_START)];
+                        screens.game.load_puzzle(puzzle);
+                        screens.game.set_ghost(None);
+                        demo = Some(Demo::new(puzzle));
+                        let next = StackChange::Switch(ScreenId::Game);
+                        change = Some(ScreenChange::Leaving(FADE, next));
                     }
-                }
-                ActiveScreen::Game => {
-                    if let Some(result) = game.update(&mut state) {
-                        menu.finish_puzzle(result);
-                        transition = Some(Transition::FadeOut(31, ActiveScreen::Menu));
+                    Some(Event::Play(puzzle)) => {
+                        screens.game.load_puzzle(puzzle);
+                        // a ghost only lines up with a run that started from an empty board
+                        match screens.menu.progress() {
+                            Some(progress) => {
+                                screens.game.load_progress(progress);
+                                screens.game.set_ghost(None);
+                            }
+                            None => screens.game.set_ghost(screens.menu.ghost()),
+                        }
+                        watching = false;
+                        let next = StackChange::Switch(ScreenId::Game);
+                        change = Some(ScreenChange::Leaving(ZOOM, next));
+                    }
+                    Some(Event::WatchBest(puzzle)) => {
+                        screens.game.load_puzzle(puzzle);
+                        screens.game.set_ghost(None);
+                        watching = true;
+                        let next = StackChange::Switch(ScreenId::Game);
+                        change = Some(ScreenChange::Leaving(ZOOM, next));
                     }
+                    Some(Event::OpenSettings) => {
+                        let next = StackChange::Push(ScreenId::Settings);
+                        change = Some(ScreenChange::Leaving(WIPE, next));
+                    }
+                    Some(Event::OpenStats) => {
+                        screens.stats.load(screens.menu.saved());
+                        let next = StackChange::Push(ScreenId::Stats);
+                        change = Some(ScreenChange::Leaving(WIPE, next));
+                    }
+                    Some(Event::OpenRecords) => {
+                        screens.records.load(screens.menu.saved());
+                        let next = StackChange::Push(ScreenId::Records);
+                        change = Some(ScreenChange::Leaving(WIPE, next));
+                    }
+                    Some(Event::ViewIllustration(illustration)) => {
+                        screens.viewer.load_illustration(illustration);
+                        let next = StackChange::Push(ScreenId::Illustration);
+                        change = Some(ScreenChange::Leaving(FADE, next));
+                    }
+                    Some(Event::Pause) => {
+                        screens.change(StackChange::Push(ScreenId::Pause), None, &state);
+                    }
+                    Some(Event::Restart) => {
+                        screens.change(StackChange::Pop, None, &state);
+                        screens.game.restart(&state);
+                    }
+                    Some(Event::ConfirmQuit) => {
+                        let quit = Event::Finished(GameResult::Quit);
+                        screens.dialog.ask(b"Quit this puzzle?", quit);
+                        screens.change(StackChange::Push(ScreenId::Dialog), None, &state);
+                    }
+                    Some(Event::EnterInitials(rank)) => {
+                        screens.name_entry.ask(rank);
+                        screens.change(StackChange::Push(ScreenId::NameEntry), None, &state);
+                    }
+                    Some(Event::InitialsEntered(initials)) => {
+                        screens.menu.save_record(initials);
+                        screens.change(StackChange::Pop, None, &state);
+                    }
+                    Some(Event::Unlock) => {
+                        let next = StackChange::Push(ScreenId::Unlock);
+                        change = Some(ScreenChange::Leaving(FADE, next));
+                    }
+                    Some(Event::Finished(result)) => {
+                        let next = if demo.is_some() {
+                            // the demo never saves, and goes back to the title
+                            state.stop_recording();
+                            demo = None;
+                            StackChange::Switch(ScreenId::Title)
+                        } else if watching {
+                            // replays never touch the save
+                            state.stop_playback();
+                            watching = false;
+                            StackChange::Switch(ScreenId::Menu)
+                        } else {
+                            state.stop_recording();
+                            let recording = screens.game.recorded().then(|| state.recording());
+                            let progress = screens.game.progress();
+                            let mistakes = screens.game.mistakes();
+                            screens
+                                .menu
+                                .finish_puzzle(result, recording, progress, mistakes);
+                            StackChange::Switch(ScreenId::Menu)
+                        };
+                        change = Some(ScreenChange::Leaving(FADE, next));
+                    }
+                    Some(Event::Close) => match screens.stack.top_transition() {
+                        // a screen closes the same way it opened
+                        Some(transition) => {
+                            change = Some(ScreenChange::Leaving(transition, StackChange::Pop));
+                        }
+                        None => screens.change(StackChange::Pop, None, &state),
+                    },
+                    None => {}
                 }
-            },
+            }
         }
     }
 }
 
 #[derive(Clone, Copy)]
-enum ActiveScreen {
-    Title,
-    Menu,
-    Game,
+enum StackChange {
+    /// Closes every screen, and opens this one instead.
+    Switch(ScreenId),
+    Push(ScreenId),
+    Pop,
 }
 
-enum Transition {
-    FadeOut(u8, ActiveScreen),
-    FadeIn(u8),
+enum ScreenChange {
+    Leaving(Transition, StackChange),
+    Entering(Transition),
+}
+
+/// Every screen in the game, and the stack of which ones are open.
+struct ScreenManager {
+    stack: ScreenStack,
+    loaded_charset: Charset,
+    title: Title,
+    menu: Menu,
+    game: Game,
+    pause: PauseMenu,
+    settings: SettingsMenu,
+    viewer: IllustrationViewer,
+    dialog: ConfirmDialog,
+    stats: StatsScreen,
+    name_entry: NameEntry,
+    records: RecordsScreen,
+    unlock: UnlockScreen,
+}
+
+impl ScreenManager {
+    fn new(root: ScreenId) -> Self {
+        let mut screens = Self {
+            stack: ScreenStack::new(root),
+            loaded_charset: Charset::Title,
+            title: Title::new(),
+            menu: Menu::new(),
+            game: Game::new(),
+            pause: PauseMenu::new(),
+            settings: SettingsMenu::new(),
+            viewer: IllustrationViewer::new(),
+            dialog: ConfirmDialog::new(),
+            stats: StatsScreen::new(),
+            name_entry: NameEntry::new(),
+            records: RecordsScreen::new(),
+            unlock: UnlockScreen::new(),
+        };
+        screens.loaded_charset = screens.get(root).charset();
+        gfx::load_character_data(screens.loaded_charset.chardata(), 0);
+        screens
+    }
+
+    fn get(&self, id: ScreenId) -> &dyn Screen {
+        match id {
+            ScreenId::Title => &self.title,
+            ScreenId::Menu => &self.menu,
+            ScreenId::Game => &self.game,
+            ScreenId::Pause => &self.pause,
+            ScreenId::Settings => &self.settings,
+            ScreenId::Illustration => &self.viewer,
+            ScreenId::Dialog => &self.dialog,
+            ScreenId::Stats => &self.stats,
+            ScreenId::NameEntry => &self.name_entry,
+            ScreenId::Records => &self.records,
+            ScreenId::Unlock => &self.unlock,
+        }
+    }
+
+    fn get_mut(&mut self, id: ScreenId) -> &mut dyn Screen {
+        match id {
+            ScreenId::Title => &mut self.title,
+            ScreenId::Menu => &mut self.menu,
+            ScreenId::Game => &mut self.game,
+            ScreenId::Pause => &mut self.pause,
+            ScreenId::Settings => &mut self.settings,
+            ScreenId::Illustration => &mut self.viewer,
+            ScreenId::Dialog => &mut self.dialog,
+            ScreenId::Stats => &mut self.stats,
+            ScreenId::NameEntry => &mut self.name_entry,
+            ScreenId::Records => &mut self.records,
+            ScreenId::Unlock => &mut self.unlock,
+        }
+    }
+
+    /// Draws every visible screen, back to front.
+    fn draw(&self, state: &GameState, worlds: &mut Worlds) {
+        for id in self.stack.visible(|id| self.get(id).is_overlay()) {
+            self.get(id).draw(state, worlds);
+        }
+    }
+
+    fn update(&mut self, state: &mut GameState) -> Option<Event> {
+        self.get_mut(self.stack.top()).update(state)
+    }
+
+    /// Opens or closes screens, then loads whatever charset the screen now on top needs.
+    fn change(&mut self, change: StackChange, transition: Option<Transition>, state: &GameState) {
+        match change {
+            StackChange::Switch(id) => self.stack.switch(id),
+            StackChange::Push(id) => {
+                // A screen that couldn't be opened leaves the one on top as it was.
+                if !self.stack.push(id, transition) {
+                    return;
+                }
+            }
+            StackChange::Pop => self.stack.pop(),
+        }
+        let charset = self.get(self.stack.top()).charset();
+        if charset != self.loaded_charset {
+            self.loaded_charset = charset;
+            gfx::load_character_data(charset.chardata(), 0);
+        }
+        let screen = self.get_mut(self.stack.top());
+        match change {
+            StackChange::Pop => screen.resume(state),
+            StackChange::Switch(_) | StackChange::Push(_) => screen.init(state),
+        }
+    }
 }
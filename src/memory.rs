@@ -0,0 +1,426 @@
+use vb_graphics::text::TextRenderer;
+
+use crate::{
+    assets,
+    name_entry::LETTERS,
+    puzzle::{ICON_CHARS, LOCK_ICON_CHARS},
+    records::{self, VISIBLE_ROWS},
+    screen::ScreenId,
+    settings::{BACK_TILES, LEFT_COLUMN_ROWS, LEFT_ROW_TILES, RIGHT_ROW_TILES, ROWS},
+    stats,
+};
+
+/// Which screens write into a region, and need it left alone while they're up.
+#[derive(Clone, Copy)]
+pub struct Owners(u16);
+
+impl Owners {
+    /// Written once at boot, so nothing else may ever touch it.
+    pub const ALWAYS: Self = Self(u16::MAX);
+
+    pub const fn of(id: ScreenId) -> Self {
+        Self(1 << id as u16)
+    }
+
+    pub const fn and(self, id: ScreenId) -> Self {
+        Self(self.0 | Self::of(id).0)
+    }
+
+    /// Whether any of these screens can be up at the same time as any of those.
+    const fn shown_with(self, other: Self) -> bool {
+        if self.0 & other.0 != 0 {
+            return true;
+        }
+        let mut index = 0;
+        while index < SHOWN_TOGETHER.len() {
+            let group = SHOWN_TOGETHER[index].0;
+            if self.0 & group != 0 && other.0 & group != 0 {
+                return true;
+            }
+            index += 1;
+        }
+        false
+    }
+}
+
+/// Screens which can be on screen together, because some of them are overlays on the others.
+const SHOWN_TOGETHER: [Owners; 2] = [
+    Owners::of(ScreenId::Game)
+        .and(ScreenId::Pause)
+        .and(ScreenId::Dialog),
+    Owners::of(ScreenId::Menu).and(ScreenId::NameEntry),
+];
+
+/// A run of character memory, reserved by name.
+#[derive(Clone, Copy)]
+pub struct Chars {
+    name: &'static str,
+    pub start: u16,
+    pub len: u16,
+    /// The size of the text renderer this was reserved for, if any.
+    pub tiles: (u8, u8),
+    owners: Owners,
+}
+
+impl Chars {
+    pub const fn at(name: &'static str, start: u16, len: usize, owners: Owners) -> Self {
+        Self {
+            name,
+            start,
+            len: len as u16,
+            tiles: (0, 0),
+            owners,
+        }
+    }
+
+    /// Room for a text renderer of the given size in tiles.
+    pub const fn text(name: &'static str, start: u16, tiles: (u8, u8), owners: Owners) -> Self {
+        let len = tiles.0 as usize * tiles.1 as usize;
+        Self {
+            tiles,
+            ..Self::at(name, start, len, owners)
+        }
+    }
+
+    /// Room for another text renderer right after this region, for the same screens.
+    pub const fn then_text(&self, name: &'static str, size: (u8, u8)) -> Self {
+        Self::text(name, self.end(), size, self.owners)
+    }
+
+    /// A text renderer in the menu font, drawing into this region.
+    pub fn text_renderer(&self) -> TextRenderer {
+        TextRenderer::new(&assets::MENU, self.start, self.tiles)
+    }
+
+    pub const fn end(&self) -> u16 {
+        self.start + self.len
+    }
+
+    const fn overlaps(&self, other: &Self) -> bool {
+        self.start < other.end() && other.start < self.end()
+    }
+}
+
+/// A background map, reserved by name.
+#[derive(Clone, Copy)]
+pub struct BgMap {
+    name: &'static str,
+    pub index: u8,
+}
+
+impl BgMap {
+    pub const fn new(name: &'static str, index: u8) -> Self {
+        Self { name, index }
+    }
+}
+
+const CHAR_COUNT: u16 = 2048;
+/// Background maps past this are where the world parameter tables and OAM live.
+const BG_MAP_COUNT: u8 = 14;
+/// Where the world parameter tables start, in halfwords past the first background map.
+pub const PARAM_TABLES: u16 = BG_MAP_COUNT as u16 * 0x1000;
+/// How many lines of affine parameters fit before the world attributes, at eight halfwords a line.
+pub const PARAM_LINES: u16 = 0xc00 / 8;
+
+/// The charsets are loaded here whole, so everything else comes after.
+pub const CHARSET: Chars = Chars::at("charset", 0, 512, Owners::ALWAYS);
+
+pub const MENU_INDEX_TEXT: Chars = Chars::text(
+    "menu id",
+    CHARSET.end(),
+    (12, 2),
+    Owners::of(ScreenId::Menu),
+);
+pub const MENU_SIZE_TEXT: Chars = MENU_INDEX_TEXT.then_text("menu size", (12, 3));
+pub const MENU_NAME_TEXT: Chars = MENU_SIZE_TEXT.then_text("menu title", (28, 3));
+pub const MENU_TIME_TEXT: Chars = MENU_NAME_TEXT.then_text("menu time", (20, 2));
+pub const MENU_VIEW_TEXT: Chars = MENU_TIME_TEXT.then_text("menu filter", (28, 2));
+/// There's no room left before the pause menu, so the page number goes after it.
+pub const MENU_PAGE_TEXT: Chars = Chars::text(
+    "menu page",
+    PAUSE_QUIT_TEXT.end(),
+    (8, 2),
+    Owners::of(ScreenId::Menu),
+);
+
+/// Initials are entered over the menu, so they go after everything the menu uses before the icons.
+pub const NAME_ENTRY_PROMPT_TEXT: Chars = Chars::text(
+    "name entry prompt",
+    MENU_PAGE_TEXT.end(),
+    (22, 2),
+    Owners::of(ScreenId::NameEntry),
+);
+/// Each letter gets its own text, so the one being changed can stand out.
+pub const NAME_ENTRY_LETTERS: Chars = Chars::at(
+    "name entry letters",
+    NAME_ENTRY_PROMPT_TEXT.end(),
+    LETTERS * 4,
+    Owners::of(ScreenId::NameEntry),
+);
+/// The name entry goes over the menu, so the watch prompt has to go after it.
+pub const MENU_WATCH_TEXT: Chars = Chars::text(
+    "menu watch",
+    NAME_ENTRY_LETTERS.end(),
+    (16, 2),
+    Owners::of(ScreenId::Menu),
+);
+
+pub const GAME_TIMER_TEXT: Chars =
+    Chars::text("timer", CHARSET.end(), (12, 2), Owners::of(ScreenId::Game));
+pub const GAME_NAME_TEXT: Chars = GAME_TIMER_TEXT.then_text("puzzle name", (26, 3));
+pub const GAME_SOURCE_TEXT: Chars = GAME_NAME_TEXT.then_text("puzzle source", (48, 3));
+pub const GAME_RUN_TEXT: Chars = Chars::text(
+    "run counter",
+    PAUSE_QUIT_TEXT.end(),
+    (8, 2),
+    Owners::of(ScreenId::Game),
+);
+
+/// The pause menu's text never changes, so it's drawn once at boot.
+pub const PAUSE_CONTINUE_TEXT: Chars = Chars::text(
+    "pause continue",
+    GAME_SOURCE_TEXT.end(),
+    (12, 2),
+    Owners::ALWAYS,
+);
+pub const PAUSE_RESTART_TEXT: Chars = PAUSE_CONTINUE_TEXT.then_text("pause restart", (12, 2));
+pub const PAUSE_QUIT_TEXT: Chars = PAUSE_RESTART_TEXT.then_text("pause quit", (12, 2));
+
+pub const DIALOG_QUESTION_TEXT: Chars = Chars::text(
+    "dialog question",
+    GAME_RUN_TEXT.end(),
+    (18, 2),
+    Owners::of(ScreenId::Dialog),
+);
+pub const DIALOG_YES_TEXT: Chars = DIALOG_QUESTION_TEXT.then_text("dialog yes", (4, 2));
+pub const DIALOG_NO_TEXT: Chars = DIALOG_YES_TEXT.then_text("dialog no", (4, 2));
+
+/// The settings rows don't fit in one run, so the left column is split around the pause menu.
+pub const SETTINGS_ROWS_BEFORE_PAUSE: usize = 7;
+pub const SETTINGS_LEFT_ROWS: Chars = Chars::at(
+    "settings rows",
+    CHARSET.end(),
+    SETTINGS_ROWS_BEFORE_PAUSE * LEFT_ROW_TILES as usize * 2,
+    Owners::of(ScreenId::Settings),
+);
+/// "Back" is short enough to squeeze in just before the pause menu.
+pub const SETTINGS_BACK_ROW: Chars = SETTINGS_LEFT_ROWS.then_text("settings back", (BACK_TILES, 2));
+pub const SETTINGS_MORE_LEFT_ROWS: Chars = Chars::at(
+    "more settings rows",
+    PAUSE_QUIT_TEXT.end(),
+    (LEFT_COLUMN_ROWS - SETTINGS_ROWS_BEFORE_PAUSE) * LEFT_ROW_TILES as usize * 2,
+    Owners::of(ScreenId::Settings),
+);
+/// The left column runs on into the menu's icons, and the right column follows it.
+/// The menu reloads its icons whenever it comes back, so the settings can borrow their characters.
+pub const SETTINGS_RIGHT_ROWS: Chars = Chars::at(
+    "settings right column",
+    SETTINGS_MORE_LEFT_ROWS.end(),
+    (ROWS - LEFT_COLUMN_ROWS - 1) * RIGHT_ROW_TILES as usize * 2,
+    Owners::of(ScreenId::Settings),
+);
+
+/// The statistics fit before the pause menu.
+pub const STATS_ROWS: Chars = Chars::at(
+    "stats rows",
+    CHARSET.end(),
+    stats::ROWS * stats::ROW_TILES as usize * 2,
+    Owners::of(ScreenId::Stats),
+);
+
+pub const RECORDS_TITLE_TEXT: Chars = Chars::text(
+    "records title",
+    CHARSET.end(),
+    (12, 2),
+    Owners::of(ScreenId::Records),
+);
+pub const UNLOCK_HEADLINE_TEXT: Chars = Chars::text(
+    "unlock headline",
+    CHARSET.end(),
+    (28, 2),
+    Owners::of(ScreenId::Unlock),
+);
+pub const UNLOCK_DETAIL_TEXT: Chars = UNLOCK_HEADLINE_TEXT.then_text("unlock detail", (28, 2));
+
+/// The records screen doesn't show any icons, so its rows can go over them.
+pub const RECORDS_ROWS: Chars = Chars::at(
+    "records rows",
+    ICONS.start,
+    VISIBLE_ROWS * records::ROW_TILES as usize * 2,
+    Owners::of(ScreenId::Records),
+);
+
+/// The statistics screen shows the solved puzzles' icons too.
+pub const ICONS: Chars = Chars::at(
+    "menu icons",
+    1024,
+    ICON_CHARS.len(),
+    Owners::of(ScreenId::Menu).and(ScreenId::Stats),
+);
+pub const LOCK_ICON: Chars = Chars::at(
+    "menu lock",
+    ICONS.end(),
+    LOCK_ICON_CHARS.len(),
+    Owners::of(ScreenId::Menu),
+);
+/// Illustrations are loaded over the menu icons, and get everything up to the cover.
+pub const ILLUSTRATIONS: Chars = Chars::at(
+    "illustrations",
+    ICONS.start,
+    (COVER.start - ICONS.start) as usize,
+    Owners::of(ScreenId::Game).and(ScreenId::Illustration),
+);
+/// A character of solid color 3. Nothing else may ever be loaded over it.
+pub const COVER: Chars = Chars::at("transition cover", CHAR_COUNT - 1, 1, Owners::ALWAYS);
+
+pub const GAME_TEXT_BG: BgMap = BgMap::new("game text", 1);
+pub const MENU_BG: BgMap = BgMap::new("menu", 2);
+pub const PAUSE_BG: BgMap = BgMap::new("pause menu", 3);
+pub const TITLE_BG: BgMap = BgMap::new("title", 4);
+pub const MEGU_BG: BgMap = BgMap::new("megu", 5);
+pub const SETTINGS_BG: BgMap = BgMap::new("settings", 6);
+/// The grid's lines and marks. Full cells are drawn as empty here, under [FILL_BG].
+pub const GRID_BG: BgMap = BgMap::new("grid", 7);
+/// The full cells, which can sit at a different depth than the rest of the grid.
+pub const FILL_BG: BgMap = BgMap::new("filled cells", 8);
+pub const ILLUSTRATION_BG: BgMap = BgMap::new("illustration", 9);
+pub const COVER_BG: BgMap = BgMap::new("transition cover", 10);
+pub const DIALOG_BG: BgMap = BgMap::new("dialog", 11);
+pub const STATS_BG: BgMap = BgMap::new("statistics", 12);
+pub const NAME_ENTRY_BG: BgMap = BgMap::new("name entry", 13);
+pub const RECORDS_BG: BgMap = BgMap::new("records", 0);
+
+const CHARS: [Chars; 33] = [
+    CHARSET,
+    MENU_INDEX_TEXT,
+    MENU_SIZE_TEXT,
+    MENU_NAME_TEXT,
+    MENU_TIME_TEXT,
+    MENU_VIEW_TEXT,
+    MENU_PAGE_TEXT,
+    NAME_ENTRY_PROMPT_TEXT,
+    NAME_ENTRY_LETTERS,
+    MENU_WATCH_TEXT,
+    GAME_TIMER_TEXT,
+    GAME_NAME_TEXT,
+    GAME_SOURCE_TEXT,
+    GAME_RUN_TEXT,
+    PAUSE_CONTINUE_TEXT,
+    PAUSE_RESTART_TEXT,
+    PAUSE_QUIT_TEXT,
+    DIALOG_QUESTION_TEXT,
+    DIALOG_YES_TEXT,
+    DIALOG_NO_TEXT,
+    SETTINGS_LEFT_ROWS,
+    SETTINGS_BACK_ROW,
+    SETTINGS_MORE_LEFT_ROWS,
+    SETTINGS_RIGHT_ROWS,
+    STATS_ROWS,
+    RECORDS_TITLE_TEXT,
+    RECORDS_ROWS,
+    UNLOCK_HEADLINE_TEXT,
+    UNLOCK_DETAIL_TEXT,
+    ICONS,
+    LOCK_ICON,
+    ILLUSTRATIONS,
+    COVER,
+];
+
+const BG_MAPS: [BgMap; 14] = [
+    GAME_TEXT_BG,
+    MENU_BG,
+    PAUSE_BG,
+    TITLE_BG,
+    MEGU_BG,
+    SETTINGS_BG,
+    GRID_BG,
+    FILL_BG,
+    ILLUSTRATION_BG,
+    COVER_BG,
+    DIALOG_BG,
+    STATS_BG,
+    NAME_ENTRY_BG,
+    RECORDS_BG,
+];
+
+/// Fails the build if a region runs off the end of memory, or overlaps another region while they
+/// can both be on screen. The error names the region at fault.
+const fn check_chars(regions: &[Chars]) {
+    let mut index = 0;
+    while index < regions.len() {
+        let region = &regions[index];
+        if region.end() > CHAR_COUNT {
+            panic!("{}", region.name);
+        }
+        let mut other = 0;
+        while other < index {
+            let earlier = &regions[other];
+            if region.overlaps(earlier) && region.owners.shown_with(earlier.owners) {
+                panic!("{}", region.name);
+            }
+            other += 1;
+        }
+        index += 1;
+    }
+}
+
+const fn check_bg_maps(maps: &[BgMap]) {
+    let mut index = 0;
+    while index < maps.len() {
+        let map = &maps[index];
+        if map.index >= BG_MAP_COUNT {
+            panic!("{}", map.name);
+        }
+        let mut other = 0;
+        while other < index {
+            if maps[other].index == map.index {
+                panic!("{}", map.name);
+            }
+            other += 1;
+        }
+        index += 1;
+    }
+}
+
+/// The most characters any one screen has in use, counting the regions of every screen that can be
+/// up along with it. [check_chars] keeps those from overlapping, so their lengths add up.
+const fn chars_used(regions: &[Chars]) -> u16 {
+    let mut most = 0;
+    let mut screen = 0;
+    while screen < u16::BITS {
+        let owners = Owners(1 << screen);
+        let mut used = 0;
+        let mut index = 0;
+        while index < regions.len() {
+            if regions[index].owners.shown_with(owners) {
+                used += regions[index].len;
+            }
+            index += 1;
+        }
+        if used > most {
+            most = used;
+        }
+        screen += 1;
+    }
+    most
+}
+
+/// How close the busiest screen comes to [CHAR_COUNT]. The illustrations take everything from
+/// the icons up to the cover, so the room left is whatever's still free below the icons.
+const CHARS_USED: u16 = chars_used(&CHARS);
+/// How many of the [BG_MAP_COUNT] background maps are reserved. [check_bg_maps] keeps them apart.
+const BG_MAPS_USED: u8 = BG_MAPS.len() as u8;
+
+// Conflicts fail the crate's own build. The regions are sized from the generated assets and
+// from consts around the crate, which build.rs can't evaluate, so they aren't part of any
+// report the asset build prints.
+const _: () = check_chars(&CHARS);
+const _: () = assert!(
+    assets::TITLE.len() <= CHARSET.len as usize && assets::ALL.len() <= CHARSET.len as usize,
+    "charset has too many characters"
+);
+const _: () = check_bg_maps(&BG_MAPS);
+const _: () = assert!(
+    CHARS_USED <= CHAR_COUNT && BG_MAPS_USED <= BG_MAP_COUNT,
+    "the layout uses more than there is"
+);
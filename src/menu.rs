@@ -1,299 +1,443 @@
-use core::fmt::Write as _;
+use core::{cmp::Ordering, fmt::Write as _};
 
-use vb_graphics::text::{BufferedTextRenderer, TextRenderer};
+use arrayvec::ArrayVec;
+use vb_graphics::{
+    self as gfx,
+    text::{BufferedTextRenderer, TextRenderer},
+};
 use vb_rt::sys::vip;
 
 use crate::{
     assets,
+    controls::{Action, RepeatProfile},
+    depth::Element,
     game::GameResult,
-    puzzle::{Puzzle, ICONS, PUZZLES},
-    save::SaveData,
+    memory,
+    pager::Pager,
+    puzzle::{
+        BONUS_START, ICON_CHAR_OFFSET, ICON_CHARS, ICONS, LOCK_ICON, LOCK_ICON_CHARS, PUZZLES,
+        Puzzle, progress_icon,
+    },
+    replay::{Ghosts, Recording},
+    save::{Progress, Record, SaveData},
+    screen::{Event, Screen},
     state::GameState,
+    worlds::Worlds,
 };
 
-const BG: u8 = 2;
+const BG: u8 = memory::MENU_BG.index;
+/// How long the filter and sort order stay on screen after they change.
+const VIEW_LABEL_FRAMES: u8 = 90;
+const COLUMNS: usize = 5;
+const ITEMS_PER_PAGE: usize = 15;
+/// How many icons go across the menu's background map, under its text.
+const ICON_BG_COLUMNS: usize = 12;
+const _: () = assert!(
+    32 + (PUZZLES.len() + 1).div_ceil(ICON_BG_COLUMNS) * 5 <= 64,
+    "not enough room in the menu's background map for every icon"
+);
+
+/// Where an icon goes in the menu's background map, in cells. The lock goes after the last puzzle.
+fn icon_cell(slot: usize) -> (u8, u8) {
+    let (row, col) = (slot / ICON_BG_COLUMNS, slot % ICON_BG_COLUMNS);
+    (col as u8 * 5, 32 + row as u8 * 5)
+}
+
+/// Which puzzles the menu shows.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Filter {
+    All,
+    Unsolved,
+    Solved,
+    /// Puzzles whose longest side is this many cells.
+    Size(usize),
+}
+impl Filter {
+    const ALL: [Filter; 7] = [
+        Self::All,
+        Self::Unsolved,
+        Self::Solved,
+        Self::Size(5),
+        Self::Size(10),
+        Self::Size(15),
+        Self::Size(20),
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::All => "All puzzles",
+            Self::Unsolved => "Unsolved",
+            Self::Solved => "Solved",
+            Self::Size(5) => "5x5",
+            Self::Size(10) => "10x10",
+            Self::Size(15) => "15x15",
+            Self::Size(_) => "20x20",
+        }
+    }
+
+    fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&f| f == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    fn matches(self, puzzle: &Puzzle, time: Option<u32>) -> bool {
+        match self {
+            Self::All => true,
+            Self::Unsolved => time.is_none(),
+            Self::Solved => time.is_some(),
+            Self::Size(size) => puzzle.width.max(puzzle.height) == size,
+        }
+    }
+}
+
+/// What order the menu shows puzzles in. Ties keep the order they're defined in.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Sort {
+    Default,
+    Size,
+    /// Fastest first, with unsolved puzzles last.
+    BestTime,
+    /// Alphabetically by the game each puzzle comes from.
+    Source,
+}
+impl Sort {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Default => "in order",
+            Self::Size => "by size",
+            Self::BestTime => "by best time",
+            Self::Source => "by game",
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            Self::Default => Self::Size,
+            Self::Size => Self::BestTime,
+            Self::BestTime => Self::Source,
+            Self::Source => Self::Default,
+        }
+    }
+
+    fn prev(self) -> Self {
+        match self {
+            Self::Default => Self::Source,
+            Self::Size => Self::Default,
+            Self::BestTime => Self::Size,
+            Self::Source => Self::BestTime,
+        }
+    }
+
+    fn compare(self, a: usize, b: usize, saved: &SaveData) -> Ordering {
+        let (puzzle_a, puzzle_b) = (&PUZZLES[a], &PUZZLES[b]);
+        let order = match self {
+            Self::Default => Ordering::Equal,
+            Self::Size => {
+                (puzzle_a.width * puzzle_a.height).cmp(&(puzzle_b.width * puzzle_b.height))
+            }
+            Self::BestTime => {
+                let time = |index: usize| saved.times[index].unwrap_or(u32::MAX);
+                time(a).cmp(&time(b))
+            }
+            Self::Source => puzzle_a.source.cmp(puzzle_b.source),
+        };
+        order.then(a.cmp(&b))
+    }
+}
 
 pub struct Menu {
-    index: usize,
-    cursor_delay: u8,
+    /// Where the cursor is in [Self::view].
+    cursor: usize,
+    /// The index in [PUZZLES] of every puzzle the menu is showing, in the order it shows them.
+    view: ArrayVec<usize, { PUZZLES.len() }>,
+    pager: Pager,
+    filter: Filter,
+    sort: Sort,
+    filter_held: bool,
+    filter_combo: bool,
     saved: SaveData,
+    /// A run which made its puzzle's records, as its index, rank and time, waiting on initials.
+    pending_record: Option<(usize, usize, u32)>,
+    /// Whether the bonus puzzles were just unlocked, and that's still to be celebrated.
+    unlock_pending: bool,
+    ghosts: Ghosts,
     index_renderer: TextRenderer,
     size_renderer: TextRenderer,
     name_renderer: BufferedTextRenderer<32>,
     time_renderer: TextRenderer,
+    view_renderer: TextRenderer,
+    view_label_timer: u8,
+    page_renderer: TextRenderer,
+    watch_renderer: TextRenderer,
+    /// The button that watches the best run, for the prompt.
+    watch_button: &'static str,
 }
 
 impl Menu {
     pub fn new() -> Self {
-        let index_renderer = TextRenderer::new(&assets::MENU, 512, (12, 2));
+        let index_renderer = memory::MENU_INDEX_TEXT.text_renderer();
         index_renderer.render_to_bgmap(BG, (0, 0));
-        let size_renderer = TextRenderer::new(&assets::MENU, 536, (12, 3));
+        let size_renderer = memory::MENU_SIZE_TEXT.text_renderer();
         size_renderer.render_to_bgmap(BG, (0, 3));
-        let name_renderer = TextRenderer::new(&assets::MENU, 572, (28, 3));
+        let name_renderer = memory::MENU_NAME_TEXT.text_renderer();
         name_renderer.render_to_bgmap(BG, (0, 6));
-        let time_renderer = TextRenderer::new(&assets::MENU, 656, (20, 2));
+        let time_renderer = memory::MENU_TIME_TEXT.text_renderer();
         time_renderer.render_to_bgmap(BG, (0, 9));
+        let view_renderer = memory::MENU_VIEW_TEXT.text_renderer();
+        view_renderer.render_to_bgmap(BG, (0, 12));
+        let page_renderer = memory::MENU_PAGE_TEXT.text_renderer();
+        page_renderer.render_to_bgmap(BG, (0, 15));
+        let watch_renderer = memory::MENU_WATCH_TEXT.text_renderer();
+        watch_renderer.render_to_bgmap(BG, (0, 18));
         assets::MENU_ARROW_LEFT.render_to_bgmap(BG, (32, 0));
         assets::MENU_ARROW_RIGHT.render_to_bgmap(BG, (32, 6));
-        for index in 0..PUZZLES.len() {
-            let row = (index / 5) as u8;
-            let col = (index % 5) as u8;
-            let dst = (col * 5, (row * 5) + 32);
-            let index = (5 * row + col) as usize;
-            ICONS[index].render_to_bgmap(BG, dst);
+        for (index, icon) in ICONS.iter().enumerate() {
+            icon.render_to_bgmap(BG, icon_cell(index));
         }
+        LOCK_ICON.render_to_bgmap(BG, icon_cell(PUZZLES.len()));
         let mut me = Self {
-            index: 0,
-            cursor_delay: 0,
+            cursor: 0,
+            view: ArrayVec::new(),
+            pager: Pager::new(ITEMS_PER_PAGE),
+            filter: Filter::All,
+            sort: Sort::Default,
+            filter_held: false,
+            filter_combo: false,
             saved: SaveData::load(),
+            pending_record: None,
+            unlock_pending: false,
+            ghosts: Ghosts::new(),
             index_renderer,
             size_renderer,
             name_renderer: name_renderer.buffered(2),
             time_renderer,
+            view_renderer,
+            view_label_timer: 0,
+            page_renderer,
+            watch_renderer,
+            watch_button: "",
         };
-        me.display_stats();
+        // a save from before there were bonus puzzles may already have every main puzzle solved
+        me.check_unlock();
+        me.update_view();
         me
     }
 
-    pub fn draw(&self) {
-        const STEREO: vip::ObjectStereo = vip::ObjectStereo::new().with_jlon(true).with_jron(true);
+    /// Records how a puzzle went. A quit board is kept to pick back up later.
+    /// Only runs which started from an empty board come with a recording.
+    /// A run fast enough for the puzzle's records asks for initials once the menu's back up.
+    pub fn finish_puzzle(
+        &mut self,
+        result: GameResult,
+        recording: Option<&Recording>,
+        progress: Progress,
+        mistakes: u16,
+    ) {
+        let Some(index) = self.selected() else {
+            return;
+        };
+        match result {
+            GameResult::Won(time) => {
+                self.saved.save_progress(index, None);
+                if self.saved.times[index].is_none_or(|t| t > time) {
+                    self.saved.save_time(index, time, mistakes);
+                    if let Some(recording) = recording {
+                        self.ghosts.save(index, recording);
+                    }
+                }
+                if let Some(rank) = self.saved.record_rank(index, time) {
+                    self.pending_record = Some((index, rank, time));
+                }
+                self.check_unlock();
+            }
+            GameResult::Quit => {
+                let progress = Some(progress).filter(Progress::is_started);
+                self.saved.save_progress(index, progress);
+            }
+        }
+    }
 
-        let mut next_world = 31;
-        let world = vip::WORLDS.index(next_world);
-        next_world -= 1;
-        world.header().write(
-            vip::WorldHeader::new()
-                .with_bgm(vip::WorldMode::Object)
-                .with_lon(true)
-                .with_ron(true),
-        );
+    /// Unlocks the bonus puzzles once every main puzzle is solved.
+    fn check_unlock(&mut self) {
+        let main_solved = self.saved.times[..BONUS_START].iter().all(Option::is_some);
+        if main_solved && !self.saved.bonus_unlocked {
+            self.saved.unlock_bonus();
+            self.unlock_pending = true;
+        }
+    }
 
-        let mut obj_index = 1023;
-        vip::SPT3.write(obj_index);
+    /// Saves the run waiting on initials into its puzzle's records.
+    pub fn save_record(&mut self, initials: [u8; 3]) {
+        if let Some((index, rank, time)) = self.pending_record.take() {
+            self.saved
+                .save_record(index, rank, Record { time, initials });
+        }
+    }
 
-        let page = self.index / 15;
+    pub fn ghost(&self) -> Option<&Recording> {
+        self.selected().and_then(|index| self.ghosts.get(index))
+    }
 
-        for index in (page * 15)..((page + 1) * 15).min(PUZZLES.len()) {
-            let index_on_page = index % 15;
-            let (row, col) = (index_on_page / 5, index_on_page % 5);
-            let dst = (52 + col as i16 * 56, 8 + row as i16 * 56);
-            let (menu_item, stereo) = if index == self.index {
-                (assets::MENU_ITEM_SELECTED, STEREO.with_jp(-4))
-            } else {
-                (assets::MENU_ITEM, STEREO)
-            };
+    pub fn saved(&self) -> &SaveData {
+        &self.saved
+    }
+
+    /// The board the selected puzzle was left at, if it was quit part way through.
+    pub fn progress(&self) -> Option<&Progress> {
+        self.selected()
+            .and_then(|index| self.saved.progress[index].as_ref())
+    }
 
-            if self.saved.times[index].is_some() {
-                let world = vip::WORLDS.index(next_world);
-                next_world -= 1;
-                world.header().write(
-                    vip::WorldHeader::new()
-                        .with_bgm(vip::WorldMode::Normal)
-                        .with_lon(true)
-                        .with_ron(true)
-                        .with_bg_map_base(BG),
-                );
-                world.gx().write(dst.0 + 8);
-                world.gp().write(if index == self.index { -4 } else { 0 });
-                world.gy().write(dst.1 + 8);
-                world.mx().write((index as i16 % 5) * 40);
-                world.my().write(256 + (index as i16 / 5) * 40);
-                world.w().write(40);
-                world.h().write(40);
+    /// Draws a thumbnail of each unsolved puzzle's board over its icon, if it's been started.
+    fn load_progress_icons(&self) {
+        for (index, puzzle) in PUZZLES.iter().enumerate() {
+            if let (None, Some(progress)) = (self.saved.times[index], &self.saved.progress[index]) {
+                let chars = progress_icon(puzzle, progress);
+                gfx::load_character_data(&chars, ICON_CHAR_OFFSET + index * chars.len());
             }
+        }
+    }
 
-            obj_index = menu_item.render_to_objects(obj_index, dst, stereo);
+    /// The index in [PUZZLES] of the puzzle under the cursor, unless the filter left nothing to show.
+    fn selected(&self) -> Option<usize> {
+        self.view.get(self.cursor).copied()
+    }
+
+    /// The bonus puzzles sit behind a lock at the end of the full list, until they're unlocked.
+    fn shows_lock(&self) -> bool {
+        !self.saved.bonus_unlocked && self.filter == Filter::All
+    }
+
+    /// How many things the cursor can land on, counting the lock.
+    fn item_count(&self) -> usize {
+        self.view.len() + self.shows_lock() as usize
+    }
+
+    /// Rebuilds the list of puzzles to show, keeping the cursor on the same puzzle if it's still there.
+    fn update_view(&mut self) {
+        let selected = self.selected();
+        self.view.clear();
+        let available = self.saved.available_puzzles();
+        for (index, puzzle) in PUZZLES.iter().enumerate().take(available) {
+            if self.filter.matches(puzzle, self.saved.times[index]) {
+                self.view.push(index);
+            }
         }
-        vip::SPT2.write(obj_index);
+        let (sort, saved) = (self.sort, &self.saved);
+        self.view
+            .sort_unstable_by(|&a, &b| sort.compare(a, b, saved));
+        self.cursor = selected
+            .and_then(|selected| self.view.iter().position(|&index| index == selected))
+            .unwrap_or(0);
+        self.pager.jump(self.cursor);
+        self.display_page();
+
+        self.view_renderer.clear();
+        let _ = write!(
+            &mut self.view_renderer,
+            "{}, {}",
+            self.filter.name(),
+            self.sort.name()
+        );
+        self.display_stats();
+    }
 
+    fn draw_stats(&self, worlds: &mut Worlds) {
         let text_height = assets::MENU.line_height as i16;
         if !self.index_renderer.is_empty() {
-            let world = vip::WORLDS.index(next_world);
-            next_world -= 1;
-            world.header().write(
-                vip::WorldHeader::new()
-                    .with_bgm(vip::WorldMode::Normal)
-                    .with_lon(true)
-                    .with_ron(true)
-                    .with_bg_map_base(BG),
-            );
-            world.gx().write(8);
-            world.gp().write(0);
-            world.gy().write(184);
-            world.mx().write(0);
-            world.my().write(0);
-            world.w().write(self.index_renderer.width() - 1);
-            world.h().write(text_height - 1);
+            let size = (self.index_renderer.width(), text_height);
+            worlds.bgmap(BG, (8, 184), 0, (0, 0), size);
         }
-
         if !self.size_renderer.is_empty() {
-            let world = vip::WORLDS.index(next_world);
-            next_world -= 1;
-            world.header().write(
-                vip::WorldHeader::new()
-                    .with_bgm(vip::WorldMode::Normal)
-                    .with_lon(true)
-                    .with_ron(true)
-                    .with_bg_map_base(BG),
-            );
-            world.gx().write(8);
-            world.gp().write(0);
-            world.gy().write(184 + text_height);
-            world.mx().write(0);
-            world.my().write(24);
-            world.w().write(self.size_renderer.width() - 1);
-            world.h().write(text_height - 1);
+            let size = (self.size_renderer.width(), text_height);
+            worlds.bgmap(BG, (8, 184 + text_height), 0, (0, 24), size);
         }
         if !self.name_renderer.is_empty() {
-            let world = vip::WORLDS.index(next_world);
-            next_world -= 1;
-            world.header().write(
-                vip::WorldHeader::new()
-                    .with_bgm(vip::WorldMode::Normal)
-                    .with_lon(true)
-                    .with_ron(true)
-                    .with_bg_map_base(BG),
-            );
-            world.gx().write(104);
-            world.gp().write(0);
-            world.gy().write(184);
-            world.mx().write(0);
-            world.my().write(48);
-            world.w().write(self.name_renderer.width() - 1);
-            world.h().write(text_height - 1);
+            let size = (self.name_renderer.width(), text_height);
+            worlds.bgmap(BG, (104, 184), 0, (0, 48), size);
         }
-
         if !self.time_renderer.is_empty() {
-            let world = vip::WORLDS.index(next_world);
-            next_world -= 1;
-            world.header().write(
-                vip::WorldHeader::new()
-                    .with_bgm(vip::WorldMode::Normal)
-                    .with_lon(true)
-                    .with_ron(true)
-                    .with_bg_map_base(BG),
-            );
-            world.gx().write(104);
-            world.gp().write(0);
-            world.gy().write(184 + text_height);
-            world.mx().write(0);
-            world.my().write(72);
-            world.w().write(self.time_renderer.width() - 1);
-            world.h().write(text_height - 1);
-        }
-
-        if page > 0 {
-            let world = vip::WORLDS.index(next_world);
-            next_world -= 1;
-            world.header().write(
-                vip::WorldHeader::new()
-                    .with_bgm(vip::WorldMode::Normal)
-                    .with_lon(true)
-                    .with_ron(true)
-                    .with_bg_map_base(BG),
-            );
-            world.gx().write(20);
-            world.gp().write(0);
-            world.gy().write(68);
-            world.mx().write(256);
-            world.my().write(0);
-            world.w().write(15);
-            world.h().write(47);
-        }
-
-        if page < PUZZLES.len() / 15 {
-            let world = vip::WORLDS.index(next_world);
-            next_world -= 1;
-            world.header().write(
-                vip::WorldHeader::new()
-                    .with_bgm(vip::WorldMode::Normal)
-                    .with_lon(true)
-                    .with_ron(true)
-                    .with_bg_map_base(BG),
-            );
-            world.gx().write(348);
-            world.gp().write(0);
-            world.gy().write(68);
-            world.mx().write(256);
-            world.my().write(48);
-            world.w().write(15);
-            world.h().write(47);
+            let size = (self.time_renderer.width(), text_height);
+            worlds.bgmap(BG, (104, 184 + text_height), 0, (0, 72), size);
+        }
+        if !self.watch_renderer.is_empty() {
+            let size = (self.watch_renderer.width(), text_height);
+            worlds.bgmap(BG, (232, 184 + text_height), 0, (0, 144), size);
         }
-
-        let world = vip::WORLDS.index(next_world);
-        world.header().write(vip::WorldHeader::new().with_end(true));
     }
 
-    pub fn update(&mut self, state: &GameState) -> Option<&'static Puzzle> {
-        self.name_renderer.update();
-
-        let pressed = state.buttons_pressed();
-        if pressed.a() {
-            return PUZZLES.get(self.index);
-        }
-        let held = state.directions_held();
-        let mut cursor_moved = false;
-        if held.ll() && self.index > 0 && self.cursor_delay == 0 {
-            self.index -= 1;
-            cursor_moved = true;
-        }
-        if held.lr() && self.index < PUZZLES.len() - 1 && self.cursor_delay == 0 {
-            self.index += 1;
-            cursor_moved = true;
-        }
-        if held.lu() && self.index > 4 && self.cursor_delay == 0 {
-            self.index -= 5;
-            cursor_moved = true;
-        }
-        if held.ld() && self.index < PUZZLES.len() - 1 && self.cursor_delay == 0 {
-            self.index = (self.index + 5).min(PUZZLES.len() - 1);
-            cursor_moved = true;
-        }
-        if cursor_moved {
-            self.cursor_delay = 4;
-            self.display_stats();
-        } else {
-            self.cursor_delay = self.cursor_delay.saturating_sub(1);
+    fn display_page(&mut self) {
+        self.page_renderer.clear();
+        let page_count = self.pager.page_count(self.item_count());
+        if page_count > 1 {
+            let _ = write!(
+                &mut self.page_renderer,
+                "{}/{}",
+                self.pager.page() + 1,
+                page_count
+            );
         }
-        None
     }
 
-    pub fn finish_puzzle(&mut self, result: GameResult) {
-        if let GameResult::Won(time) = result {
-            if self.saved.times[self.index].is_none_or(|t| t > time) {
-                self.saved.save_time(self.index, time);
-            }
+    /// Moves the cursor, and turns the page if it landed on another one.
+    fn move_cursor(&mut self, cursor: usize) {
+        self.cursor = cursor;
+        if self.pager.follow(cursor) {
+            self.display_page();
         }
+        self.display_stats();
     }
 
-    pub fn init(&mut self) {
-        self.display_stats();
+    fn show_view_change(&mut self) {
+        self.update_view();
+        self.view_label_timer = VIEW_LABEL_FRAMES;
     }
 
     fn display_stats(&mut self) {
-        let puzzle = &PUZZLES[self.index];
-        let (done, seconds) = match self.saved.times[self.index] {
+        self.index_renderer.clear();
+        self.size_renderer.clear();
+        self.name_renderer.clear();
+        self.time_renderer.clear();
+        self.watch_renderer.clear();
+        if self.ghost().is_some() {
+            let _ = write!(
+                &mut self.watch_renderer,
+                "{}: watch best",
+                self.watch_button
+            );
+        }
+        let Some(index) = self.selected() else {
+            if self.shows_lock() && self.cursor == self.view.len() {
+                let _ = write!(&mut self.index_renderer, "locked");
+                let _ = write!(&mut self.name_renderer.inner, "bonus puzzles");
+                let _ = write!(&mut self.time_renderer, "solve all {BONUS_START}");
+            }
+            return;
+        };
+        let puzzle = &PUZZLES[index];
+        let (done, seconds) = match self.saved.times[index] {
             Some(time) => (true, time / 50),
             None => (false, 0),
         };
 
-        self.index_renderer.clear();
-        let _ = write!(&mut self.index_renderer, "id: {}", self.index + 1);
+        let _ = write!(&mut self.index_renderer, "id: {}", index + 1);
 
-        self.size_renderer.clear();
         let _ = write!(
             &mut self.size_renderer,
             "size: {}x{}",
             puzzle.width, puzzle.height
         );
 
-        self.name_renderer.clear();
         let _ = write!(&mut self.name_renderer.inner, "title: ");
         if done {
             self.name_renderer.draw_text(puzzle.name);
         }
 
-        self.time_renderer.clear();
+        if let (false, Some(progress)) = (done, &self.saved.progress[index]) {
+            let percent = progress.percent_complete(puzzle);
+            let _ = write!(&mut self.time_renderer, "done: {percent}%");
+            return;
+        }
         let _ = write!(&mut self.time_renderer, "time: ");
         if done {
             let _ = write!(
@@ -306,3 +450,173 @@ impl Menu {
         }
     }
 }
+
+impl Screen for Menu {
+    fn init(&mut self, state: &GameState) {
+        // the controls might have been changed in the settings since the menu was last up
+        self.watch_button = state.settings().controls.button(Action::Cross).name();
+        // an illustration may have been loaded over the icons since the menu was last up
+        gfx::load_character_data(&ICON_CHARS, ICON_CHAR_OFFSET);
+        gfx::load_character_data(&LOCK_ICON_CHARS, memory::LOCK_ICON.start as usize);
+        self.load_progress_icons();
+        // solving a puzzle can move it in or out of the view
+        self.update_view();
+        self.filter_held = false;
+        self.filter_combo = false;
+    }
+
+    fn resume(&mut self, state: &GameState) {
+        self.init(state);
+    }
+
+    fn draw(&self, state: &GameState, worlds: &mut Worlds) {
+        const STEREO: vip::ObjectStereo = vip::ObjectStereo::new().with_jlon(true).with_jron(true);
+        let settings = state.settings();
+
+        worlds.objects();
+        let mut obj_index = 1023;
+        vip::SPT3.write(obj_index);
+
+        let items = self.pager.items(self.item_count());
+        let offset = self.pager.offset();
+
+        for (position, item) in items.clone().enumerate() {
+            let (row, col) = (position / COLUMNS, position % COLUMNS);
+            let dst = (52 + col as i16 * 56 + offset, 8 + row as i16 * 56);
+            let (menu_item, element) = if item == self.cursor {
+                (assets::MENU_ITEM_SELECTED, Element::SelectedMenuItem)
+            } else {
+                (assets::MENU_ITEM, Element::MenuItem)
+            };
+            let parallax = settings.depth.parallax(element);
+
+            // past the end of the view is the lock
+            let icon = match self.view.get(item) {
+                Some(&index) => (self.saved.times[index].is_some()
+                    || self.saved.progress[index].is_some())
+                .then_some(index),
+                None => Some(PUZZLES.len()),
+            };
+            if let Some(slot) = icon {
+                let (x, y) = icon_cell(slot);
+                let src = (x as i16 * 8, y as i16 * 8);
+                worlds.bgmap(BG, (dst.0 + 8, dst.1 + 8), parallax, src, (41, 41));
+            }
+
+            obj_index = menu_item.render_to_objects(obj_index, dst, STEREO.with_jp(parallax));
+        }
+        vip::SPT2.write(obj_index);
+
+        let text_height = assets::MENU.line_height as i16;
+        if self.view_label_timer > 0 || self.view.is_empty() {
+            // the filter and sort order take the stats' place for a moment after they change
+            let x = 192 - self.view_renderer.width() / 2;
+            let size = (self.view_renderer.width(), text_height);
+            worlds.bgmap(BG, (x, 184 + text_height / 2), 0, (0, 96), size);
+        } else {
+            self.draw_stats(worlds);
+        }
+
+        if !self.page_renderer.is_empty() {
+            let x = 376 - self.page_renderer.width();
+            let size = (self.page_renderer.width(), text_height);
+            worlds.bgmap(BG, (x, 184 + text_height / 2), 0, (0, 120), size);
+        }
+
+        if self.pager.has_prev() {
+            worlds.bgmap(BG, (20, 68), 0, (256, 0), (16, 48));
+        }
+
+        if self.pager.has_next(self.item_count()) {
+            worlds.bgmap(BG, (348, 68), 0, (256, 48), (16, 48));
+        }
+    }
+
+    fn update(&mut self, state: &mut GameState) -> Option<Event> {
+        self.name_renderer.update();
+        self.view_label_timer = self.view_label_timer.saturating_sub(1);
+        self.pager.update();
+        if let Some((_, rank, _)) = self.pending_record {
+            return Some(Event::EnterInitials(rank));
+        }
+        if self.unlock_pending {
+            self.unlock_pending = false;
+            return Some(Event::Unlock);
+        }
+
+        // Filter on its own cycles the filter when released, Filter+Left/Right cycles the sort order,
+        // Filter+Up opens the statistics and Filter+Down opens the records
+        let pressed = state.actions_pressed();
+        let filter_held = state.actions_held().contains(Action::Filter);
+        if filter_held && pressed.contains(Action::Up) {
+            return Some(Event::OpenStats);
+        }
+        if filter_held && pressed.contains(Action::Down) {
+            return Some(Event::OpenRecords);
+        }
+        if filter_held && (pressed.contains(Action::Left) || pressed.contains(Action::Right)) {
+            self.sort = if pressed.contains(Action::Left) {
+                self.sort.prev()
+            } else {
+                self.sort.next()
+            };
+            self.filter_combo = true;
+            self.show_view_change();
+        }
+        if self.filter_held && !filter_held {
+            if !self.filter_combo {
+                self.filter = self.filter.next();
+                self.show_view_change();
+            }
+            self.filter_combo = false;
+        }
+        self.filter_held = filter_held;
+        if filter_held {
+            return None;
+        }
+
+        if pressed.contains(Action::Pause) {
+            return Some(Event::OpenSettings);
+        }
+        if let Some(selected) = self.selected() {
+            if pressed.contains(Action::Fill) {
+                return Some(Event::Play(&PUZZLES[selected]));
+            }
+            if pressed.contains(Action::Cross) && self.ghost().is_some() {
+                return Some(Event::WatchBest(&PUZZLES[selected]));
+            }
+            if pressed.contains(Action::ViewArt) && self.saved.times[selected].is_some() {
+                if let Some(illustration) = PUZZLES[selected].illustration {
+                    return Some(Event::ViewIllustration(illustration));
+                }
+            }
+        }
+        let last = self.item_count().checked_sub(1)?;
+        let per_page = self.pager.per_page();
+        if pressed.contains(Action::PrevPage) && self.pager.has_prev() {
+            self.move_cursor(self.cursor - per_page);
+        }
+        if pressed.contains(Action::NextPage) && self.pager.has_next(self.item_count()) {
+            self.move_cursor((self.cursor + per_page).min(last));
+        }
+
+        let held = state.actions_repeated(RepeatProfile::Menu);
+        let mut cursor = self.cursor;
+        if held.contains(Action::Left) && cursor > 0 {
+            cursor -= 1;
+        }
+        if held.contains(Action::Right) && cursor < last {
+            cursor += 1;
+        }
+        if held.contains(Action::Up) && cursor >= COLUMNS {
+            cursor -= COLUMNS;
+        }
+        if held.contains(Action::Down) && cursor < last {
+            cursor = (cursor + COLUMNS).min(last);
+        }
+        if cursor != self.cursor {
+            self.move_cursor(cursor);
+        }
+        None
+    }
+}
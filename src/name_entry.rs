@@ -0,0 +1,127 @@
+use core::fmt::Write as _;
+
+use vb_graphics::text::TextRenderer;
+
+use crate::{
+    assets,
+    controls::{Action, RepeatProfile},
+    memory,
+    screen::{Event, Screen},
+    state::GameState,
+    transition,
+    worlds::Worlds,
+};
+
+const BG: u8 = memory::NAME_ENTRY_BG.index;
+pub const LETTERS: usize = 3;
+
+fn ordinal(rank: usize) -> &'static str {
+    match rank {
+        0 => "1st",
+        1 => "2nd",
+        _ => "3rd",
+    }
+}
+
+/// Asks for three letters of initials over whatever screen is open, one letter at a time.
+/// Up and down change a letter, Fill moves on to the next and Cross goes back.
+/// The last initials entered are offered again next time.
+pub struct NameEntry {
+    initials: [u8; LETTERS],
+    /// Which letter is being changed.
+    position: usize,
+    prompt_text: TextRenderer,
+    letter_texts: [TextRenderer; LETTERS],
+}
+
+impl NameEntry {
+    pub fn new() -> Self {
+        let prompt_text = memory::NAME_ENTRY_PROMPT_TEXT.text_renderer();
+        prompt_text.render_to_bgmap(BG, (0, 0));
+        let letter_texts = core::array::from_fn(|index| {
+            let offset = memory::NAME_ENTRY_LETTERS.start + index as u16 * 4;
+            let text = TextRenderer::new(&assets::MENU, offset, (2, 2));
+            text.render_to_bgmap(BG, (index as u8 * 2, 2));
+            text
+        });
+        Self {
+            initials: *b"AAA",
+            position: 0,
+            prompt_text,
+            letter_texts,
+        }
+    }
+
+    /// Sets up the prompt for a run which placed at this rank.
+    pub fn ask(&mut self, rank: usize) {
+        self.position = 0;
+        self.prompt_text.clear();
+        let _ = write!(&mut self.prompt_text, "New record! {} place", ordinal(rank));
+    }
+
+    fn display_letters(&mut self) {
+        for (text, &letter) in self.letter_texts.iter_mut().zip(&self.initials) {
+            text.clear();
+            text.draw_text(&[letter]);
+        }
+    }
+
+    fn change_letter(&mut self, forward: bool) {
+        let letter = &mut self.initials[self.position];
+        let index = *letter - b'A';
+        let index = if forward { index + 1 } else { index + 25 };
+        *letter = b'A' + index % 26;
+        self.display_letters();
+    }
+}
+
+impl Screen for NameEntry {
+    fn is_overlay(&self) -> bool {
+        true
+    }
+
+    fn init(&mut self, _state: &GameState) {
+        // other screens borrow these characters while this one's closed
+        self.display_letters();
+    }
+
+    fn draw(&self, _state: &GameState, worlds: &mut Worlds) {
+        let text_height = assets::MENU.line_height as i16;
+        let prompt_width = self.prompt_text.width();
+        // blank out whatever's behind the prompt
+        transition::draw_cover((96, 84), (192, text_height * 2 + 16), worlds);
+        let pos = (192 - prompt_width / 2, 92);
+        worlds.bgmap(BG, pos, -8, (0, 0), (prompt_width, text_height));
+        for index in 0..LETTERS {
+            let x = 192 - 24 + index as i16 * 16;
+            let parallax = if index == self.position { -10 } else { -6 };
+            let src = (index as i16 * 16, 16);
+            worlds.bgmap(BG, (x, 92 + text_height), parallax, src, (16, text_height));
+        }
+    }
+
+    fn update(&mut self, state: &mut GameState) -> Option<Event> {
+        let held = state.actions_repeated(RepeatProfile::Menu);
+        if held.contains(Action::Up) {
+            self.change_letter(true);
+        }
+        if held.contains(Action::Down) {
+            self.change_letter(false);
+        }
+        let pressed = state.actions_pressed();
+        if (pressed.contains(Action::Left) || pressed.contains(Action::Cross)) && self.position > 0
+        {
+            self.position -= 1;
+        }
+        if pressed.contains(Action::Right) && self.position < LETTERS - 1 {
+            self.position += 1;
+        }
+        if pressed.contains(Action::Fill) {
+            if self.position == LETTERS - 1 {
+                return Some(Event::InitialsEntered(self.initials));
+            }
+            self.position += 1;
+        }
+        None
+    }
+}
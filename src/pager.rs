@@ -0,0 +1,89 @@
+use core::ops::Range;
+
+use crate::transition::{Easing, FULL};
+
+/// How long a new page takes to slide in, in frames.
+const SLIDE_FRAMES: u8 = 12;
+/// How far off to the side a new page starts sliding in from.
+/// Objects wrap around past x=511, so the last column can't start much further right than this.
+const SLIDE_DISTANCE: i32 = 96;
+
+/// Splits a list of any length into pages of the same size, and slides each new page in from
+/// the side it came from. Only the new page moves, so a slide never needs more worlds than a page.
+pub struct Pager {
+    per_page: usize,
+    page: usize,
+    /// Frames left in the current slide.
+    slide_frames: u8,
+    /// Whether the current slide is coming in from the right, towards later pages.
+    slide_forward: bool,
+}
+
+impl Pager {
+    pub const fn new(per_page: usize) -> Self {
+        Self {
+            per_page,
+            page: 0,
+            slide_frames: 0,
+            slide_forward: false,
+        }
+    }
+
+    pub fn per_page(&self) -> usize {
+        self.per_page
+    }
+
+    pub fn page(&self) -> usize {
+        self.page
+    }
+
+    /// How many pages a list this long takes up. An empty list still gets one empty page.
+    pub fn page_count(&self, len: usize) -> usize {
+        len.div_ceil(self.per_page).max(1)
+    }
+
+    /// Which items of a list this long are on the current page.
+    pub fn items(&self, len: usize) -> Range<usize> {
+        let start = (self.page * self.per_page).min(len);
+        start..(start + self.per_page).min(len)
+    }
+
+    pub fn has_prev(&self) -> bool {
+        self.page > 0
+    }
+
+    pub fn has_next(&self, len: usize) -> bool {
+        self.page + 1 < self.page_count(len)
+    }
+
+    /// Turns to whichever page the cursor is on, sliding it in if that's a new page.
+    /// Returns true if the page changed.
+    pub fn follow(&mut self, cursor: usize) -> bool {
+        let page = cursor / self.per_page;
+        if page == self.page {
+            return false;
+        }
+        self.slide_forward = page > self.page;
+        self.slide_frames = SLIDE_FRAMES;
+        self.page = page;
+        true
+    }
+
+    /// Turns to whichever page the cursor is on without sliding, for when the whole list changed.
+    pub fn jump(&mut self, cursor: usize) {
+        self.page = cursor / self.per_page;
+        self.slide_frames = 0;
+    }
+
+    pub fn update(&mut self) {
+        self.slide_frames = self.slide_frames.saturating_sub(1);
+    }
+
+    /// How far to the side of its resting place the current page should be drawn.
+    pub fn offset(&self) -> i16 {
+        let progress = (SLIDE_FRAMES - self.slide_frames) as u32 * FULL / SLIDE_FRAMES as u32;
+        let left = (FULL - Easing::Out.apply(progress)) as i32;
+        let offset = (SLIDE_DISTANCE * left / FULL as i32) as i16;
+        if self.slide_forward { offset } else { -offset }
+    }
+}
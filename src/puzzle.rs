@@ -1,15 +1,43 @@
 use vb_graphics::Image;
 use vb_rt::sys::vip;
 
+use crate::{
+    assets,
+    game::{MAX_PUZZLE_SIZE, PuzzleCell},
+    memory,
+    save::Progress,
+};
+
+#[derive(Clone, Copy)]
 pub struct Puzzle {
     pub name: &'static [u8],
     pub source: &'static [u8],
     pub width: usize,
     pub height: usize,
     pub cells: &'static [u8],
+    pub illustration: Option<&'static Illustration>,
+}
+
+/// A full picture of what a puzzle depicts, shown once it's solved.
+/// Each one has its own chardata, loaded over the menu icons whenever it's on screen.
+pub struct Illustration {
+    pub chardata: &'static [vip::Character],
+    pub image: Image,
 }
 
-const fn menu_icon_row(mut cells: &[u8]) -> [u16; 5] {
+pub const ILLUSTRATION_CHAR_OFFSET: usize = memory::ILLUSTRATIONS.start as usize;
+pub const ILLUSTRATION_MAX_CHARS: usize = memory::ILLUSTRATIONS.len as usize;
+/// Illustrations have to fit above the puzzle's name on the win screen.
+pub const ILLUSTRATION_MAX_CELLS: (usize, usize) = (48, 23);
+pub const ILLUSTRATION_PALETTE: u8 = 1;
+
+/// The shade a solved puzzle's full cells are drawn in on its menu icon.
+const ICON_SHADES: [u16; 4] = [0b00, 0b10, 0b00, 0b00];
+/// The shades of a board in progress: empty cells, filled cells, then crossed out cells.
+const PROGRESS_SHADES: [u16; 4] = [0b00, 0b10, 0b01, 0b00];
+
+/// Draws one row of cells, two pixels apiece, with each cell's value picking its shade.
+const fn menu_icon_row(mut cells: &[u8], shades: [u16; 4]) -> [u16; 5] {
     let offset = 20 - cells.len();
     let mut result = [0; 5];
     let mut dst_index = offset;
@@ -17,18 +45,21 @@ const fn menu_icon_row(mut cells: &[u8]) -> [u16; 5] {
         cells = rest;
         let target_dst_index = dst_index + 2;
         while dst_index < target_dst_index {
-            if cell != 0 {
-                let dst_hw = &mut result[dst_index / 8];
-                let dst_offset = dst_index % 8;
-                *dst_hw |= 0b10 << (dst_offset * 2);
-            }
+            let dst_hw = &mut result[dst_index / 8];
+            let dst_offset = dst_index % 8;
+            *dst_hw |= shades[cell as usize % 4] << (dst_offset * 2);
             dst_index += 1;
         }
     }
     result
 }
 
-const fn menu_icon(mut cells: &[u8], width: usize, height: usize) -> [vip::Character; 25] {
+const fn menu_icon(
+    mut cells: &[u8],
+    width: usize,
+    height: usize,
+    shades: [u16; 4],
+) -> [vip::Character; 25] {
     let offset = 20 - height;
     let mut result = [vip::Character([0; 8]); 25];
     if width == 0 || height == 0 {
@@ -36,7 +67,7 @@ const fn menu_icon(mut cells: &[u8], width: usize, height: usize) -> [vip::Chara
     }
     let mut dst_index = offset;
     while let Some((row, rest)) = cells.split_at_checked(width) {
-        let src_row = menu_icon_row(row);
+        let src_row = menu_icon_row(row, shades);
         cells = rest;
 
         let target_dst_index = dst_index + 2;
@@ -60,12 +91,31 @@ const fn menu_icon_chars<const N: usize>(puzzles: [Puzzle; N]) -> [[vip::Charact
     let mut index = 0;
     while index < N {
         let puzzle = &puzzles[index];
-        result[index] = menu_icon(puzzle.cells, puzzle.width, puzzle.height);
+        result[index] = menu_icon(puzzle.cells, puzzle.width, puzzle.height, ICON_SHADES);
         index += 1;
     }
     result
 }
 
+/// Draws a board in progress in the same layout as a menu icon, to load over that puzzle's icon.
+pub fn progress_icon(puzzle: &Puzzle, progress: &Progress) -> [vip::Character; 25] {
+    let mut cells = [0; MAX_PUZZLE_SIZE * MAX_PUZZLE_SIZE];
+    let cell_count = puzzle.width * puzzle.height;
+    for (index, cell) in cells.iter_mut().take(cell_count).enumerate() {
+        *cell = match progress.cell(index) {
+            PuzzleCell::Empty | PuzzleCell::Dot => 0,
+            PuzzleCell::Full => 1,
+            PuzzleCell::Cross => 2,
+        };
+    }
+    menu_icon(
+        &cells[..cell_count],
+        puzzle.width,
+        puzzle.height,
+        PROGRESS_SHADES,
+    )
+}
+
 const fn menu_icon_cells<const N: usize>(offset: u16) -> [[vip::Cell; 25]; N] {
     let mut result = [[vip::Cell::new(); 25]; N];
     let mut index = 0;
@@ -100,12 +150,50 @@ const fn menu_icon_images<const N: usize>(cells: &'static [[vip::Cell; 25]; N])
     result
 }
 
+/// Points an illustration's cells at wherever its characters get loaded, and at its own palette.
+const fn illustration_cells<const N: usize>(cells: &[vip::Cell]) -> [vip::Cell; N] {
+    let mut result = [vip::Cell::new(); N];
+    let mut index = 0;
+    while index < N {
+        let cell = cells[index];
+        result[index] = cell
+            .with_character(cell.character() + ILLUSTRATION_CHAR_OFFSET as u16)
+            .with_palette(ILLUSTRATION_PALETTE);
+        index += 1;
+    }
+    result
+}
+
+macro_rules! illustration {
+    ($image:expr, $chardata:expr) => {{
+        const _: () = assert!(
+            $chardata.len() <= ILLUSTRATION_MAX_CHARS,
+            "illustration has too many characters"
+        );
+        const _: () = assert!(
+            $image.width_cells as usize <= ILLUSTRATION_MAX_CELLS.0
+                && $image.height_cells as usize <= ILLUSTRATION_MAX_CELLS.1,
+            "illustration is too big for the win screen"
+        );
+        const CELLS: [vip::Cell; $image.data.len()] = illustration_cells($image.data);
+        Illustration {
+            chardata: &$chardata,
+            image: Image {
+                width_cells: $image.width_cells,
+                height_cells: $image.height_cells,
+                data: &CELLS,
+            },
+        }
+    }};
+}
+
 pub const EMPTY: Puzzle = Puzzle {
     name: &[],
     source: &[],
     width: 0,
     height: 0,
     cells: &[],
+    illustration: None,
 };
 
 const fn format_puzzle<const N: usize>(input: &[u8]) -> [u8; N] {
@@ -134,6 +222,14 @@ macro_rules! puzzle {
             width: $width,
             height: $height,
             cells: &PUZZLE_CELLS,
+            illustration: None,
+        }
+    }};
+    ($name:expr, $source:expr, ($width:expr, $height:expr), $puzzle:expr, $illustration:expr) => {{
+        const ILLUSTRATION: Illustration = $illustration;
+        Puzzle {
+            illustration: Some(&ILLUSTRATION),
+            ..puzzle!($name, $source, ($width, $height), $puzzle)
         }
     }};
 }
@@ -159,7 +255,11 @@ pub const GOLF_BALL: Puzzle = puzzle!(
     -xxx-
     xxxxx
     -xxx-
-    --x--"
+    --x--",
+    illustration!(
+        assets::GOLF_BALL_ILLUSTRATION,
+        assets::GOLF_BALL_ILLUSTRATION_CHARS
+    )
 );
 
 pub const HEART: Puzzle = puzzle!(
@@ -171,7 +271,8 @@ pub const HEART: Puzzle = puzzle!(
     xxxxx
     xxxxx
     -xxx-
-    --x--"
+    --x--",
+    illustration!(assets::HEART_ILLUSTRATION, assets::HEART_ILLUSTRATION_CHARS)
 );
 
 pub const BOWLING_PIN: Puzzle = puzzle!(
@@ -713,7 +814,135 @@ pub const MEGU: Puzzle = puzzle!(
     -------xx--xx-------"
 );
 
-pub const PUZZLES: [Puzzle; 30] = [
+pub const CHECKERED_FLAG: Puzzle = puzzle!(
+    b"Checkered Flag",
+    b"Zero Racers",
+    (10, 10),
+    b"
+    xxxxxxxxx-
+    xxx--xx--x
+    xxx--xx--x
+    x--xx--xxx
+    x--xx--xxx
+    xxx--xx--x
+    xxx--xx--x
+    xxxxxxxxx-
+    x---------
+    x---------"
+);
+
+pub const SMILOID: Puzzle = puzzle!(
+    b"Smiloid",
+    b"Faceball",
+    (10, 10),
+    b"
+    --xxxxxx--
+    -xxxxxxxx-
+    xxx-xx-xxx
+    xxx-xx-xxx
+    xxxxxxxxxx
+    xx-xxxx-xx
+    xxx-xx-xxx
+    xxxx--xxxx
+    -xxxxxxxx-
+    --xxxxxx--"
+);
+
+pub const DRAGON_EGG: Puzzle = puzzle!(
+    b"Dragon Egg",
+    b"Dragon Hopper",
+    (10, 15),
+    b"
+    ---xxxx---
+    --xxxxxx--
+    -xxx--xxx-
+    -xx----xx-
+    -xxx--xxxx
+    xxxxxxxx-x
+    xxxxxxx---
+    xx--xxxx-x
+    x----xxxxx
+    xx--xxxxxx
+    xxxxxx--xx
+    -xxxxx--x-
+    -xxxxxxxx-
+    --xxxxxx--
+    ----xx----"
+);
+
+pub const MAZE: Puzzle = puzzle!(
+    b"Maze",
+    b"Faceball",
+    (15, 15),
+    b"
+    xxxxxxxxxxxxxxx
+    x-----x-------x
+    x-xxx-x-xxxxx-x
+    x-x---x-----x-x
+    x-x-xxxxxxx-x-x
+    x-x-x-----x---x
+    x-x-x-xxx-xxxxx
+    x---x-x-x-----x
+    xxxxx-x-xxxxx-x
+    x-----x-----x-x
+    x-xxxxxxxxx-x-x
+    x-x---------x-x
+    x-x-xxxxxxxxx-x
+    x---x---------x
+    xxxxxxxxxxxxx-x"
+);
+
+pub const RACER: Puzzle = puzzle!(
+    b"Racer",
+    b"Zero Racers",
+    (20, 15),
+    b"
+    ---------xx---------
+    --------xxxx--------
+    --------x--x--------
+    -------xx--xx-------
+    -------x-xx-x-------
+    ---xx-xx-xx-xx-xx---
+    --xxxxx-xxxx-xxxxx--
+    -xx--xx-x--x-xx--xx-
+    xx----xxx--xxx----xx
+    xxxxxxxx-xx-xxxxxxxx
+    --xxxxx-xxxx-xxxxx--
+    ----xx--x--x--xx----
+    ----xx-xxxxxx-xx----
+    ---xxxx------xxxx---
+    ---x--x------x--x---"
+);
+
+pub const DRAGON: Puzzle = puzzle!(
+    b"Dragon",
+    b"Dragon Hopper",
+    (20, 20),
+    b"
+    ----xx--------------
+    ---xxxx-------------
+    --xxxxxxx-----------
+    -xxx-xxxxx----------
+    xxxxxxxxxxx---------
+    xxxxxxxxxxxx--------
+    -x-xxxxxxxxx--x-----
+    ----xxxxxxxx-xxx----
+    -----xxxxxxxxxxxx---
+    ----xxxxxxxxxxxxxx--
+    ---xxx--xxxxxxxxxx--
+    --xxx-xxxxxxxxxxxxx-
+    --xx-xxxxxxxxxxxxxx-
+    -----xxxxxxxxxx-xxx-
+    ----xxxxxxxxxx---xxx
+    ----xxx-xxxxx-----xx
+    ----xx--xxxx------xx
+    ---xxx--xxx------xx-
+    --xxxx-xxxx----xxx--
+    --xxx--xxxx-xxxx----"
+);
+
+/// The puzzles there from the start.
+pub const MAIN_PUZZLES: [Puzzle; 30] = [
     TETRIS_BLOCK,
     GOLF_BALL,
     HEART,
@@ -746,8 +975,54 @@ pub const PUZZLES: [Puzzle; 30] = [
     MEGU,
 ];
 
+/// Puzzles from games that never came out, locked until every one of the main puzzles is solved.
+pub const BONUS_PUZZLES: [Puzzle; 6] = [CHECKERED_FLAG, SMILOID, DRAGON_EGG, MAZE, RACER, DRAGON];
+
+const fn all_puzzles<const N: usize>(main: &[Puzzle], bonus: &[Puzzle]) -> [Puzzle; N] {
+    assert!(main.len() + bonus.len() == N);
+    let mut result = [EMPTY; N];
+    let mut index = 0;
+    while index < N {
+        result[index] = if index < main.len() {
+            main[index]
+        } else {
+            bonus[index - main.len()]
+        };
+        index += 1;
+    }
+    result
+}
+
+/// Every puzzle, main then bonus. A puzzle's index in here is where everything about it is saved.
+pub const PUZZLES: [Puzzle; MAIN_PUZZLES.len() + BONUS_PUZZLES.len()] =
+    all_puzzles(&MAIN_PUZZLES, &BONUS_PUZZLES);
+/// The index of the first bonus puzzle in [PUZZLES].
+pub const BONUS_START: usize = MAIN_PUZZLES.len();
+
 pub const ICON_CHARS: [vip::Character; PUZZLES.len() * 25] =
     unsafe { core::mem::transmute(menu_icon_chars(PUZZLES)) };
-pub const ICON_CHAR_OFFSET: usize = 1024;
-pub const ICON_CELLS: [[vip::Cell; 25]; PUZZLES.len()] = menu_icon_cells(1024);
+pub const ICON_CHAR_OFFSET: usize = memory::ICONS.start as usize;
+pub const ICON_CELLS: [[vip::Cell; 25]; PUZZLES.len()] = menu_icon_cells(memory::ICONS.start);
 pub const ICONS: [Image; PUZZLES.len()] = menu_icon_images(&ICON_CELLS);
+
+const LOCK_CELLS: [u8; 100] = format_puzzle(
+    b"
+    ---xxxx---
+    --x----x--
+    --x----x--
+    --x----x--
+    -xxxxxxxx-
+    -xxxxxxxx-
+    -xxx--xxx-
+    -xxx--xxx-
+    -xxxxxxxx-
+    -xxxxxxxx-",
+);
+/// A padlock, in the same style as the icons, standing in for the bonus puzzles until they're unlocked.
+pub const LOCK_ICON_CHARS: [vip::Character; 25] = menu_icon(&LOCK_CELLS, 10, 10, ICON_SHADES);
+const LOCK_ICON_CELLS: [vip::Cell; 25] = menu_icon_cells::<1>(memory::LOCK_ICON.start)[0];
+pub const LOCK_ICON: Image = Image {
+    width_cells: 5,
+    height_cells: 5,
+    data: &LOCK_ICON_CELLS,
+};
@@ -0,0 +1,154 @@
+use core::fmt::Write as _;
+
+use vb_graphics::text::TextRenderer;
+
+use crate::{
+    assets,
+    controls::{Action, RepeatProfile},
+    memory,
+    puzzle::PUZZLES,
+    save::{RECORDS_PER_PUZZLE, Record, SaveData},
+    screen::{Event, Screen},
+    state::GameState,
+    stats::write_time,
+    worlds::Worlds,
+};
+
+const BG: u8 = memory::RECORDS_BG.index;
+/// How many puzzles fit on screen at once.
+pub const VISIBLE_ROWS: usize = 10;
+const ID_TILES: u8 = 5;
+const RECORD_TILES: u8 = 11;
+/// A row is the puzzle's id and then its records, side by side.
+pub const ROW_TILES: u8 = ID_TILES + RECORD_TILES * RECORDS_PER_PUZZLE as u8;
+
+/// One puzzle's row of text.
+struct Row {
+    id_text: TextRenderer,
+    record_texts: [TextRenderer; RECORDS_PER_PUZZLE],
+}
+
+impl Row {
+    /// Lays out a row's text in the characters and background map row which belong to it.
+    fn new(index: usize) -> Self {
+        let start = memory::RECORDS_ROWS.start + index as u16 * ROW_TILES as u16 * 2;
+        let bg_row = 2 + index as u8 * 2;
+        let id_text = TextRenderer::new(&assets::MENU, start, (ID_TILES, 2));
+        id_text.render_to_bgmap(BG, (0, bg_row));
+        let record_texts = core::array::from_fn(|rank| {
+            let tiles_before = ID_TILES + rank as u8 * RECORD_TILES;
+            let offset = start + tiles_before as u16 * 2;
+            let text = TextRenderer::new(&assets::MENU, offset, (RECORD_TILES, 2));
+            text.render_to_bgmap(BG, (tiles_before, bg_row));
+            text
+        });
+        Self {
+            id_text,
+            record_texts,
+        }
+    }
+
+    fn display(&mut self, puzzle: usize, records: &[Option<Record>; RECORDS_PER_PUZZLE]) {
+        self.id_text.clear();
+        let _ = write!(&mut self.id_text, "#{}", puzzle + 1);
+        for (text, record) in self.record_texts.iter_mut().zip(records) {
+            text.clear();
+            let _ = match record {
+                Some(record) => {
+                    text.draw_text(&record.initials);
+                    write!(text, " ").and_then(|_| write_time(text, Some(record.time)))
+                }
+                None => write!(text, "---"),
+            };
+        }
+    }
+}
+
+/// Lists every puzzle's fastest runs, a screenful at a time. Up and down scroll through them.
+pub struct RecordsScreen {
+    records: [[Option<Record>; RECORDS_PER_PUZZLE]; PUZZLES.len()],
+    /// How many puzzles to list. The bonus puzzles stay hidden until they're unlocked.
+    available: usize,
+    /// The puzzle at the top of the screen.
+    top: usize,
+    title_text: TextRenderer,
+    rows: [Row; VISIBLE_ROWS],
+}
+
+impl RecordsScreen {
+    pub fn new() -> Self {
+        let title_text = memory::RECORDS_TITLE_TEXT.text_renderer();
+        title_text.render_to_bgmap(BG, (0, 0));
+        Self {
+            records: [[None; RECORDS_PER_PUZZLE]; PUZZLES.len()],
+            available: PUZZLES.len(),
+            top: 0,
+            title_text,
+            rows: core::array::from_fn(Row::new),
+        }
+    }
+
+    /// Takes a copy of the records to show next time the screen opens.
+    pub fn load(&mut self, saved: &SaveData) {
+        self.records = saved.records;
+        self.available = saved.available_puzzles();
+        self.top = 0;
+    }
+
+    fn visible_rows(&self) -> usize {
+        VISIBLE_ROWS.min(self.available)
+    }
+
+    fn display_rows(&mut self) {
+        for (offset, row) in self.rows.iter_mut().enumerate() {
+            let puzzle = self.top + offset;
+            if let Some(records) = self.records[..self.available].get(puzzle) {
+                row.display(puzzle, records);
+            }
+        }
+    }
+}
+
+impl Screen for RecordsScreen {
+    fn init(&mut self, _state: &GameState) {
+        // other screens borrow these characters while this one's closed
+        self.title_text.clear();
+        self.title_text.draw_text(b"Records");
+        self.display_rows();
+    }
+
+    fn draw(&self, _state: &GameState, worlds: &mut Worlds) {
+        let text_height = assets::MENU.line_height as i16;
+        let width = ROW_TILES as i16 * 8;
+        let x = 192 - width / 2;
+        let title_size = (self.title_text.width(), text_height);
+        worlds.bgmap(BG, (x, 12), 0, (0, 0), title_size);
+        // every row's laid out one under the other, so they can all share a world
+        let height = self.visible_rows() as i16 * 16;
+        worlds.bgmap(BG, (x, 36), 0, (0, 16), (width, height));
+    }
+
+    fn update(&mut self, state: &mut GameState) -> Option<Event> {
+        let pressed = state.actions_pressed();
+        if pressed.contains(Action::Fill)
+            || pressed.contains(Action::Cross)
+            || pressed.contains(Action::Pause)
+        {
+            return Some(Event::Close);
+        }
+        let held = state.actions_repeated(RepeatProfile::Menu);
+        let last_top = self.available - self.visible_rows();
+        let top = if held.contains(Action::Up) {
+            self.top.saturating_sub(1)
+        } else if held.contains(Action::Down) {
+            (self.top + 1).min(last_top)
+        } else {
+            self.top
+        };
+        if top != self.top {
+            self.top = top;
+            self.display_rows();
+        }
+        None
+    }
+}
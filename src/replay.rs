@@ -0,0 +1,137 @@
+use arrayvec::ArrayVec;
+
+use crate::{save::Settings, state::InputSnapshot};
+
+const MAX_INPUTS: usize = 1024;
+const GHOST_SLOTS: usize = 4;
+const MAX_PAUSES: usize = 32;
+
+/// The controller stream of one run, stored as (buttons, frames held) pairs.
+/// Along with the seed and settings it started with, this is enough to replay it exactly.
+#[derive(Clone)]
+pub struct Recording {
+    seed: u64,
+    settings: Settings,
+    start: Option<InputSnapshot>,
+    inputs: ArrayVec<(u16, u16), MAX_INPUTS>,
+    /// How many frames have been pushed so far.
+    frames: u32,
+    /// The frames the run was paused on and resumed on, so anything following along
+    /// knows which inputs went to the pause menu instead of the board.
+    pauses: ArrayVec<(u32, u32), MAX_PAUSES>,
+    overflowed: bool,
+}
+
+impl Recording {
+    pub fn new() -> Self {
+        Self {
+            seed: 0,
+            settings: Settings::default(),
+            start: None,
+            inputs: ArrayVec::new(),
+            frames: 0,
+            pauses: ArrayVec::new(),
+            overflowed: false,
+        }
+    }
+
+    pub fn begin(&mut self, seed: u64, settings: Settings, start: InputSnapshot) {
+        self.seed = seed;
+        self.settings = settings;
+        self.start = Some(start);
+        self.inputs.clear();
+        self.frames = 0;
+        self.pauses.clear();
+        self.overflowed = false;
+    }
+
+    pub fn push(&mut self, bits: u16) {
+        if self.overflowed {
+            return;
+        }
+        self.frames += 1;
+        if let Some((last_bits, frames)) = self.inputs.last_mut() {
+            if *last_bits == bits && *frames < u16::MAX {
+                *frames += 1;
+                return;
+            }
+        }
+        if self.inputs.try_push((bits, 1)).is_err() {
+            self.overflowed = true;
+        }
+    }
+
+    /// Notes that the run was paused on the frame just pushed.
+    pub fn mark_paused(&mut self) {
+        if self.pauses.try_push((self.frames, u32::MAX)).is_err() {
+            self.overflowed = true;
+        }
+    }
+
+    /// Notes that the run picked back up on the frame just pushed.
+    pub fn mark_resumed(&mut self) {
+        if let Some((_, resumed)) = self.pauses.last_mut() {
+            *resumed = self.frames;
+        }
+    }
+
+    /// Whether the run was sitting in the pause menu on the given frame.
+    pub fn is_paused(&self, frame: u32) -> bool {
+        self.pauses
+            .iter()
+            .any(|&(paused, resumed)| paused < frame && frame < resumed)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.start.is_some() && !self.overflowed
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    pub fn start(&self) -> Option<&InputSnapshot> {
+        self.start.as_ref()
+    }
+
+    pub fn input(&self, index: usize) -> Option<(u16, u16)> {
+        self.inputs.get(index).copied()
+    }
+}
+
+/// Best-run recordings for the puzzles beaten most recently, kept for as long as the console's on.
+/// A full recording takes 4KiB of inputs alone, and SRAM's 8KiB is all but used up already,
+/// so they never make it into the save.
+pub struct Ghosts {
+    slots: ArrayVec<(usize, Recording), GHOST_SLOTS>,
+}
+
+impl Ghosts {
+    pub fn new() -> Self {
+        Self {
+            slots: ArrayVec::new(),
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Recording> {
+        self.slots
+            .iter()
+            .find(|(i, _)| *i == index)
+            .map(|(_, recording)| recording)
+    }
+
+    pub fn save(&mut self, index: usize, recording: &Recording) {
+        self.slots.retain(|(i, _)| *i != index);
+        if !recording.is_complete() {
+            return;
+        }
+        if self.slots.is_full() {
+            self.slots.remove(0);
+        }
+        self.slots.push((index, recording.clone()));
+    }
+}
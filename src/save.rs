@@ -1,6 +1,54 @@
 use vb_rt::sys::sram;
 
-use crate::puzzle::PUZZLES;
+use crate::{
+    controls::{Action, Controls, KeyRepeat, Preset, RepeatProfile},
+    depth::DepthPreset,
+    game::{MAX_PUZZLE_SIZE, PuzzleCell},
+    puzzle::{BONUS_START, PUZZLES, Puzzle},
+};
+
+/// The main puzzles' best times are checksummed here, where saves from before the bonus puzzles
+/// already have it, and the bonus puzzles' times get their own checksum just after.
+const MAIN_TIMES_CHECKSUM_OFFSET: usize = 0;
+const BONUS_TIMES_CHECKSUM_OFFSET: usize = 2;
+const SEED_OFFSET: usize = 4;
+/// Holds [UNLOCK_MAGIC] once the bonus puzzles are unlocked. Anything else, noise included, means
+/// they're still locked.
+const UNLOCK_OFFSET: usize = 12;
+const UNLOCK_MAGIC: [u8; 4] = *b"BONU";
+const SETTINGS_OFFSET: usize = 16;
+const TIMES_OFFSET: usize = 256;
+/// How many mistakes each puzzle's best run made, two bytes apiece after a checksum, in the gap
+/// between the best times and the records.
+const MISTAKES_OFFSET: usize = 400;
+const MISTAKES_LEN: usize = PUZZLES.len() * 2;
+/// Nothing is ever saved here, so on a fresh cartridge it's whatever noise the SRAM powered on with.
+const NOISE_RANGE: core::ops::Range<usize> = 4096..4352;
+const SETTINGS_LEN: usize = Action::ALL.len() + 9;
+/// Boards left part way through get a slot each, after the noise.
+const PROGRESS_OFFSET: usize = NOISE_RANGE.end;
+/// Every cell's mark takes two bits.
+const PROGRESS_CELL_BYTES: usize = MAX_PUZZLE_SIZE * MAX_PUZZLE_SIZE / 4;
+/// A checksum, the time, then the cells.
+const PROGRESS_SLOT_LEN: usize = 2 + 4 + PROGRESS_CELL_BYTES;
+const SRAM_LEN: usize = 8192;
+const _: () = assert!(
+    PROGRESS_OFFSET + PUZZLES.len() * PROGRESS_SLOT_LEN <= SRAM_LEN,
+    "not enough SRAM to save every puzzle's progress"
+);
+pub const RECORDS_PER_PUZZLE: usize = 3;
+/// Every puzzle's fastest runs get a slot each, between the best times and the noise.
+const RECORDS_OFFSET: usize = 512;
+/// A time, then three letters of initials.
+const RECORD_LEN: usize = 4 + 3;
+/// A checksum, then the records from fastest to slowest.
+const RECORDS_SLOT_LEN: usize = 2 + RECORDS_PER_PUZZLE * RECORD_LEN;
+const _: () = assert!(
+    TIMES_OFFSET + PUZZLES.len() * 4 <= MISTAKES_OFFSET
+        && MISTAKES_OFFSET + 2 + MISTAKES_LEN <= RECORDS_OFFSET
+        && RECORDS_OFFSET + PUZZLES.len() * RECORDS_SLOT_LEN <= NOISE_RANGE.start,
+    "not enough SRAM to save every puzzle's records"
+);
 
 struct Fletcher {
     s1: u16,
@@ -24,39 +72,382 @@ impl Fletcher {
 
 pub struct SaveData {
     pub times: [Option<u32>; PUZZLES.len()],
+    /// How many mistakes the run behind each best time made.
+    pub mistakes: [u16; PUZZLES.len()],
+    pub progress: [Option<Progress>; PUZZLES.len()],
+    /// Each puzzle's fastest runs, fastest first.
+    pub records: [[Option<Record>; RECORDS_PER_PUZZLE]; PUZZLES.len()],
+    pub bonus_unlocked: bool,
 }
 
 impl SaveData {
     pub fn load() -> Self {
+        let mut times = [None; PUZZLES.len()];
+        let (main_times, bonus_times) = times.split_at_mut(BONUS_START);
+        let main_loaded = load_times(main_times, 0, MAIN_TIMES_CHECKSUM_OFFSET);
+        load_times(bonus_times, BONUS_START, BONUS_TIMES_CHECKSUM_OFFSET);
+
+        let progress = core::array::from_fn(Progress::load);
+        let records = core::array::from_fn(load_records);
+        let unlock: [u8; 4] = sram::SRAM.read_array(UNLOCK_OFFSET);
+        Self {
+            times,
+            mistakes: load_mistakes(),
+            progress,
+            records,
+            // if the main times were lost, the bonus puzzles have to be earned again
+            bonus_unlocked: main_loaded && unlock == UNLOCK_MAGIC,
+        }
+    }
+
+    /// Saves a puzzle's best time, along with how many mistakes the run made.
+    pub fn save_time(&mut self, index: usize, time: u32, mistakes: u16) {
+        self.times[index] = Some(time);
+        let (block, checksum_offset) = if index < BONUS_START {
+            (0..BONUS_START, MAIN_TIMES_CHECKSUM_OFFSET)
+        } else {
+            (BONUS_START..PUZZLES.len(), BONUS_TIMES_CHECKSUM_OFFSET)
+        };
         let mut fletcher = Fletcher::new();
-        let times = core::array::from_fn(|index| {
-            let mut bytes = [0; 4];
-            sram::SRAM.read_slice(&mut bytes, 256 + index * 4);
-            fletcher.add_many(bytes);
-            let time = u32::from_le_bytes(bytes);
-            if time > 0 { Some(time) } else { None }
-        });
+        for time in &self.times[block] {
+            fletcher.add_many(time.unwrap_or_default().to_le_bytes());
+        }
+        let checksum = fletcher.finish();
+        sram::SRAM.write_slice(&time.to_le_bytes(), TIMES_OFFSET + index * 4);
+        sram::SRAM.write_slice(&checksum.to_le_bytes(), checksum_offset);
 
-        let expected_checksum = fletcher.finish();
-        let actual_checksum = u16::from_le_bytes(sram::SRAM.read_array(0));
-        if expected_checksum == actual_checksum {
-            Self { times }
+        // the whole block is written, since a save from before mistakes were kept has noise here
+        self.mistakes[index] = mistakes;
+        let mut bytes = [0; MISTAKES_LEN];
+        for (chunk, mistakes) in bytes.chunks_exact_mut(2).zip(self.mistakes) {
+            chunk.copy_from_slice(&mistakes.to_le_bytes());
+        }
+        let mut fletcher = Fletcher::new();
+        fletcher.add_many(bytes);
+        sram::SRAM.write_slice(&bytes, MISTAKES_OFFSET + 2);
+        sram::SRAM.write_slice(&fletcher.finish().to_le_bytes(), MISTAKES_OFFSET);
+    }
+
+    pub fn unlock_bonus(&mut self) {
+        self.bonus_unlocked = true;
+        sram::SRAM.write_slice(&UNLOCK_MAGIC, UNLOCK_OFFSET);
+    }
+
+    /// How many of [PUZZLES] can be played, which leaves out the bonus puzzles until they're unlocked.
+    pub fn available_puzzles(&self) -> usize {
+        if self.bonus_unlocked {
+            PUZZLES.len()
         } else {
-            for index in 0..PUZZLES.len() * 4 {
-                sram::SRAM.index(256 + index).write(0);
+            BONUS_START
+        }
+    }
+
+    /// Keeps a board to pick back up later, or forgets it if there's nothing to keep.
+    pub fn save_progress(&mut self, index: usize, progress: Option<Progress>) {
+        self.progress[index] = progress;
+        let progress = progress.unwrap_or(Progress::new(0));
+        let mut bytes = [0; PROGRESS_SLOT_LEN - 2];
+        let (time, cells) = bytes.split_at_mut(4);
+        time.copy_from_slice(&progress.time.to_le_bytes());
+        cells.copy_from_slice(&progress.cells);
+        let mut fletcher = Fletcher::new();
+        fletcher.add_many(bytes);
+        let offset = PROGRESS_OFFSET + index * PROGRESS_SLOT_LEN;
+        sram::SRAM.write_slice(&bytes, offset + 2);
+        sram::SRAM.write_slice(&fletcher.finish().to_le_bytes(), offset);
+    }
+
+    /// Where a run with this time would place among a puzzle's records, if it places at all.
+    /// A time has to beat a record to push it down, so the earlier of two tied runs stays ahead.
+    pub fn record_rank(&self, index: usize, time: u32) -> Option<usize> {
+        self.records[index]
+            .iter()
+            .position(|record| record.is_none_or(|record| time < record.time))
+    }
+
+    /// Puts a run into a puzzle's records at the given rank, pushing the slower ones down.
+    pub fn save_record(&mut self, index: usize, rank: usize, record: Record) {
+        let records = &mut self.records[index];
+        records[rank..].rotate_right(1);
+        records[rank] = Some(record);
+        let mut bytes = [0; RECORDS_SLOT_LEN - 2];
+        for (chunk, record) in bytes.chunks_exact_mut(RECORD_LEN).zip(records.iter()) {
+            if let Some(record) = record {
+                chunk.copy_from_slice(&record.to_bytes());
             }
-            Self { times: [None; PUZZLES.len()] }
         }
+        let mut fletcher = Fletcher::new();
+        fletcher.add_many(bytes);
+        let offset = RECORDS_OFFSET + index * RECORDS_SLOT_LEN;
+        sram::SRAM.write_slice(&bytes, offset + 2);
+        sram::SRAM.write_slice(&fletcher.finish().to_le_bytes(), offset);
     }
+}
 
-    pub fn save_time(&mut self, index: usize, time: u32) {
-        self.times[index] = Some(time);
+/// Reads one checksummed block of best times, for the puzzles from `first` on. If the checksum
+/// doesn't match, the block is cleared, and this returns false.
+fn load_times(times: &mut [Option<u32>], first: usize, checksum_offset: usize) -> bool {
+    let offset = TIMES_OFFSET + first * 4;
+    let mut fletcher = Fletcher::new();
+    for (index, time) in times.iter_mut().enumerate() {
+        let bytes: [u8; 4] = sram::SRAM.read_array(offset + index * 4);
+        fletcher.add_many(bytes);
+        let value = u32::from_le_bytes(bytes);
+        *time = if value > 0 { Some(value) } else { None };
+    }
+    let expected_checksum = fletcher.finish();
+    let actual_checksum = u16::from_le_bytes(sram::SRAM.read_array(checksum_offset));
+    if expected_checksum == actual_checksum {
+        return true;
+    }
+    times.fill(None);
+    for index in 0..times.len() * 4 {
+        sram::SRAM.index(offset + index).write(0);
+    }
+    // a block of zeroes checksums to zero
+    sram::SRAM.write_slice(&0u16.to_le_bytes(), checksum_offset);
+    false
+}
+
+/// Reads every puzzle's mistakes. If the checksum doesn't match, they're all taken as none, which
+/// is also what puzzles solved before mistakes were kept show.
+fn load_mistakes() -> [u16; PUZZLES.len()] {
+    let bytes: [u8; MISTAKES_LEN] = sram::SRAM.read_array(MISTAKES_OFFSET + 2);
+    let mut fletcher = Fletcher::new();
+    fletcher.add_many(bytes);
+    let expected_checksum = fletcher.finish();
+    let actual_checksum = u16::from_le_bytes(sram::SRAM.read_array(MISTAKES_OFFSET));
+    if expected_checksum != actual_checksum {
+        return [0; PUZZLES.len()];
+    }
+    let mut mistakes = bytes
+        .chunks_exact(2)
+        .map(|chunk| u16::from_le_bytes([chunk[0], chunk[1]]));
+    core::array::from_fn(|_| mistakes.next().unwrap_or_default())
+}
+
+fn load_records(index: usize) -> [Option<Record>; RECORDS_PER_PUZZLE] {
+    let offset = RECORDS_OFFSET + index * RECORDS_SLOT_LEN;
+    let bytes: [u8; RECORDS_SLOT_LEN - 2] = sram::SRAM.read_array(offset + 2);
+    let mut fletcher = Fletcher::new();
+    fletcher.add_many(bytes);
+    let expected_checksum = fletcher.finish();
+    let actual_checksum = u16::from_le_bytes(sram::SRAM.read_array(offset));
+    if expected_checksum != actual_checksum {
+        return [None; RECORDS_PER_PUZZLE];
+    }
+    let mut records = bytes.chunks_exact(RECORD_LEN).map(Record::from_bytes);
+    core::array::from_fn(|_| records.next().flatten())
+}
+
+/// One of a puzzle's fastest runs, and who ran it.
+#[derive(Clone, Copy)]
+pub struct Record {
+    pub time: u32,
+    /// Three capital letters.
+    pub initials: [u8; 3],
+}
+
+impl Record {
+    fn to_bytes(self) -> [u8; RECORD_LEN] {
+        let mut bytes = [0; RECORD_LEN];
+        bytes[..4].copy_from_slice(&self.time.to_le_bytes());
+        bytes[4..].copy_from_slice(&self.initials);
+        bytes
+    }
+
+    /// An empty record is all zeroes, which has no time on it.
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let (time, initials) = bytes.split_at(4);
+        let time = u32::from_le_bytes(time.try_into().ok()?);
+        let initials: [u8; 3] = initials.try_into().ok()?;
+        let valid = time > 0 && initials.iter().all(u8::is_ascii_uppercase);
+        valid.then_some(Self { time, initials })
+    }
+}
+
+/// A board left part way through, and how long had been spent on it so far.
+#[derive(Clone, Copy)]
+pub struct Progress {
+    pub time: u32,
+    /// Every cell's mark, two bits apiece, row by row.
+    cells: [u8; PROGRESS_CELL_BYTES],
+}
+
+impl Progress {
+    pub fn new(time: u32) -> Self {
+        Self {
+            time,
+            cells: [0; PROGRESS_CELL_BYTES],
+        }
+    }
+
+    fn load(index: usize) -> Option<Self> {
+        let offset = PROGRESS_OFFSET + index * PROGRESS_SLOT_LEN;
+        let bytes: [u8; PROGRESS_SLOT_LEN - 2] = sram::SRAM.read_array(offset + 2);
         let mut fletcher = Fletcher::new();
-        for time in self.times {
-            fletcher.add_many(time.unwrap_or_default().to_le_bytes());
+        fletcher.add_many(bytes);
+        let expected_checksum = fletcher.finish();
+        let actual_checksum = u16::from_le_bytes(sram::SRAM.read_array(offset));
+        let (time, cells) = bytes.split_at(4);
+        let time = u32::from_le_bytes(time.try_into().ok()?);
+        // an empty slot is all zeroes, which checks out but has no time on it
+        if expected_checksum != actual_checksum || time == 0 {
+            return None;
+        }
+        Some(Self {
+            time,
+            cells: cells.try_into().ok()?,
+        })
+    }
+
+    pub fn cell(&self, index: usize) -> PuzzleCell {
+        match (self.cells[index / 4] >> ((index % 4) * 2)) & 0b11 {
+            0 => PuzzleCell::Empty,
+            1 => PuzzleCell::Full,
+            2 => PuzzleCell::Cross,
+            _ => PuzzleCell::Dot,
+        }
+    }
+
+    pub fn set_cell(&mut self, index: usize, cell: PuzzleCell) {
+        let bits = match cell {
+            PuzzleCell::Empty => 0,
+            PuzzleCell::Full => 1,
+            PuzzleCell::Cross => 2,
+            PuzzleCell::Dot => 3,
+        };
+        let shift = (index % 4) * 2;
+        let byte = &mut self.cells[index / 4];
+        *byte = (*byte & !(0b11 << shift)) | (bits << shift);
+    }
+
+    /// Whether any cell has been marked at all.
+    pub fn is_started(&self) -> bool {
+        self.cells.iter().any(|&byte| byte != 0)
+    }
+
+    /// How much of the puzzle is done, as a percentage. Only cells which have been filled or crossed
+    /// out correctly count, so a dot or a wrong guess never looks like progress.
+    pub fn percent_complete(&self, puzzle: &Puzzle) -> usize {
+        let cell_count = puzzle.width * puzzle.height;
+        let correct = (0..cell_count)
+            .filter(|&index| match self.cell(index) {
+                PuzzleCell::Full => puzzle.cells[index] == 1,
+                PuzzleCell::Cross => puzzle.cells[index] == 0,
+                PuzzleCell::Empty | PuzzleCell::Dot => false,
+            })
+            .count();
+        correct * 100 / cell_count.max(1)
+    }
+}
+
+/// Picks an rng seed for this boot, mixing the seed stored last boot with SRAM noise.
+/// A new seed is stored for next time, so every boot plays out differently.
+pub fn boot_seed() -> u64 {
+    let mut seed = u64::from_le_bytes(sram::SRAM.read_array(SEED_OFFSET));
+    for index in NOISE_RANGE {
+        let byte = sram::SRAM.index(index).read();
+        seed = (seed ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    let next_seed = seed.rotate_left(17) ^ 0x9e37_79b9_7f4a_7c15;
+    sram::SRAM.write_slice(&next_seed.to_le_bytes(), SEED_OFFSET);
+    seed
+}
+
+#[derive(Clone, Copy)]
+pub struct Settings {
+    pub controls: Controls,
+    pub menu_repeat: KeyRepeat,
+    pub board_repeat: KeyRepeat,
+    /// Keeps each fill or cross stroke on the row or column it started moving along.
+    pub axis_lock: bool,
+    /// Draws faint lines along the cursor's row and column.
+    pub guide_lines: bool,
+    pub depth: DepthPreset,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            controls: Preset::Default.controls(),
+            menu_repeat: KeyRepeat::DEFAULT,
+            board_repeat: KeyRepeat::DEFAULT,
+            axis_lock: true,
+            guide_lines: false,
+            depth: DepthPreset::Subtle,
         }
+    }
+}
+
+impl Settings {
+    pub fn load() -> Self {
+        let bytes: [u8; SETTINGS_LEN] = sram::SRAM.read_array(SETTINGS_OFFSET + 2);
+        let mut fletcher = Fletcher::new();
+        fletcher.add_many(bytes);
+
+        let expected_checksum = fletcher.finish();
+        let actual_checksum = u16::from_le_bytes(sram::SRAM.read_array(SETTINGS_OFFSET));
+        let settings = if expected_checksum == actual_checksum {
+            Self::from_bytes(bytes)
+        } else {
+            None
+        };
+        settings.unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        let bytes = self.to_bytes();
+        let mut fletcher = Fletcher::new();
+        fletcher.add_many(bytes);
         let checksum = fletcher.finish();
-        sram::SRAM.write_slice(&time.to_le_bytes(), 256 + index * 4);
-        sram::SRAM.write_slice(&checksum.to_le_bytes(), 0);
+        sram::SRAM.write_slice(&bytes, SETTINGS_OFFSET + 2);
+        sram::SRAM.write_slice(&checksum.to_le_bytes(), SETTINGS_OFFSET);
+    }
+
+    pub fn repeat(&self, profile: RepeatProfile) -> &KeyRepeat {
+        match profile {
+            RepeatProfile::Menu => &self.menu_repeat,
+            RepeatProfile::Board => &self.board_repeat,
+        }
+    }
+
+    pub fn repeat_mut(&mut self, profile: RepeatProfile) -> &mut KeyRepeat {
+        match profile {
+            RepeatProfile::Menu => &mut self.menu_repeat,
+            RepeatProfile::Board => &mut self.board_repeat,
+        }
+    }
+
+    fn to_bytes(self) -> [u8; SETTINGS_LEN] {
+        let mut bytes = [0; SETTINGS_LEN];
+        let (controls, rest) = bytes.split_at_mut(Action::ALL.len());
+        controls.copy_from_slice(&self.controls.to_bytes());
+        rest[0..3].copy_from_slice(&self.menu_repeat.to_bytes());
+        rest[3..6].copy_from_slice(&self.board_repeat.to_bytes());
+        rest[6] = self.axis_lock as u8;
+        rest[7] = self.guide_lines as u8;
+        rest[8] = self.depth.to_byte();
+        bytes
+    }
+
+    fn from_bytes(bytes: [u8; SETTINGS_LEN]) -> Option<Self> {
+        let (controls, rest) = bytes.split_at(Action::ALL.len());
+        Some(Self {
+            controls: Controls::from_bytes(controls.try_into().ok()?)?,
+            menu_repeat: KeyRepeat::from_bytes(rest[0..3].try_into().ok()?)?,
+            board_repeat: KeyRepeat::from_bytes(rest[3..6].try_into().ok()?)?,
+            axis_lock: bool_from_byte(rest[6])?,
+            guide_lines: bool_from_byte(rest[7])?,
+            depth: DepthPreset::from_byte(rest[8])?,
+        })
+    }
+}
+
+fn bool_from_byte(byte: u8) -> Option<bool> {
+    match byte {
+        0 => Some(false),
+        1 => Some(true),
+        _ => None,
     }
 }
@@ -0,0 +1,147 @@
+use arrayvec::ArrayVec;
+use vb_rt::sys::vip::Character;
+
+use crate::{
+    assets,
+    game::GameResult,
+    puzzle::{Illustration, Puzzle},
+    state::GameState,
+    transition::Transition,
+    worlds::Worlds,
+};
+
+/// The character set a screen draws its images from, which is loaded at character 0 before it's shown.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Charset {
+    Title,
+    All,
+}
+impl Charset {
+    pub fn chardata(self) -> &'static [Character] {
+        match self {
+            Self::Title => &assets::TITLE,
+            Self::All => &assets::ALL,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ScreenId {
+    Title,
+    Menu,
+    Game,
+    Pause,
+    Settings,
+    Illustration,
+    Dialog,
+    Stats,
+    NameEntry,
+    Records,
+    Unlock,
+}
+
+/// Something a screen wants to happen, which usually means opening or closing screens.
+#[derive(Clone, Copy)]
+pub enum Event {
+    Start,
+    Demo,
+    Play(&'static Puzzle),
+    WatchBest(&'static Puzzle),
+    OpenSettings,
+    OpenStats,
+    OpenRecords,
+    ViewIllustration(&'static Illustration),
+    Pause,
+    Restart,
+    /// Asks before quitting the puzzle in progress.
+    ConfirmQuit,
+    /// Asks for initials for a run which placed at this rank in its puzzle's records.
+    EnterInitials(usize),
+    InitialsEntered([u8; 3]),
+    /// Celebrates the bonus puzzles being unlocked.
+    Unlock,
+    Finished(GameResult),
+    /// Closes the screen on top, going back to the one underneath.
+    Close,
+}
+
+pub trait Screen {
+    fn charset(&self) -> Charset {
+        Charset::All
+    }
+
+    /// Whether the screens underneath this one stay visible, and keep their place in the worlds behind it.
+    /// Overlays have to share a charset with whatever they're opened over.
+    fn is_overlay(&self) -> bool {
+        false
+    }
+
+    fn init(&mut self, state: &GameState);
+
+    /// Called when the screen on top of this one closes.
+    fn resume(&mut self, _state: &GameState) {}
+
+    /// Draws in front of whatever's already taken the worlds handed out so far.
+    fn draw(&self, state: &GameState, worlds: &mut Worlds);
+
+    /// Only the screen on top of the stack is updated.
+    fn update(&mut self, state: &mut GameState) -> Option<Event>;
+}
+
+const MAX_DEPTH: usize = 4;
+
+/// The screens currently open, from the bottom up.
+/// Each remembers the transition it was opened with, to close with the same one.
+pub struct ScreenStack(ArrayVec<(ScreenId, Option<Transition>), MAX_DEPTH>);
+
+impl ScreenStack {
+    pub fn new(root: ScreenId) -> Self {
+        let mut stack = ArrayVec::new();
+        stack.push((root, None));
+        Self(stack)
+    }
+
+    pub fn top(&self) -> ScreenId {
+        self.0.last().map_or(ScreenId::Title, |&(id, _)| id)
+    }
+
+    /// The transition the top screen was opened with.
+    pub fn top_transition(&self) -> Option<Transition> {
+        self.0.last().and_then(|&(_, transition)| transition)
+    }
+
+    pub fn contains(&self, id: ScreenId) -> bool {
+        self.0.iter().any(|&(open, _)| open == id)
+    }
+
+    /// Opens a screen over the others. If the stack is already as deep as it goes, nothing is
+    /// opened and this returns false.
+    pub fn push(&mut self, id: ScreenId, transition: Option<Transition>) -> bool {
+        let pushed = self.0.try_push((id, transition)).is_ok();
+        debug_assert!(pushed, "too many screens open, at most {MAX_DEPTH} fit");
+        pushed
+    }
+
+    /// Closes the top screen, though the one at the bottom always stays open.
+    pub fn pop(&mut self) {
+        if self.0.len() > 1 {
+            self.0.pop();
+        }
+    }
+
+    /// Closes every screen, and opens this one in their place.
+    pub fn switch(&mut self, id: ScreenId) {
+        self.0.clear();
+        self.0.push((id, None));
+    }
+
+    /// Every open screen which can be seen, from the back to the front.
+    pub fn visible(&self, is_overlay: impl Fn(ScreenId) -> bool) -> impl Iterator<Item = ScreenId> {
+        let start = self
+            .0
+            .iter()
+            .rposition(|&(id, _)| !is_overlay(id))
+            .unwrap_or(0);
+        self.0[start..].iter().map(|&(id, _)| id)
+    }
+}
@@ -0,0 +1,289 @@
+use core::fmt::Write as _;
+
+use vb_graphics::text::TextRenderer;
+
+use crate::{
+    assets,
+    controls::{Action, Button, KeyRepeat, Preset, RepeatProfile},
+    memory::{self, SETTINGS_ROWS_BEFORE_PAUSE},
+    save::Settings,
+    screen::{Event, Screen},
+    state::GameState,
+    worlds::Worlds,
+};
+
+const BG: u8 = memory::SETTINGS_BG.index;
+pub const ROWS: usize = Action::ALL.len() + 11;
+/// The preset and the bindings, which run down the left of the screen and wrap into a second column.
+pub const LEFT_COLUMN_ROWS: usize = Action::ALL.len() + 1;
+/// How many rows fit down the screen.
+const COLUMN_ROWS: usize = 13;
+const _: () = assert!(
+    LEFT_COLUMN_ROWS <= COLUMN_ROWS * 2 && ROWS - LEFT_COLUMN_ROWS <= COLUMN_ROWS,
+    "the settings don't fit on screen"
+);
+pub const LEFT_ROW_TILES: u8 = 16;
+pub const RIGHT_ROW_TILES: u8 = 14;
+pub const BACK_TILES: u8 = 11;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum RepeatField {
+    Delay,
+    Rate,
+    Accel,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Row {
+    Preset,
+    Binding(Action),
+    Repeat(RepeatProfile, RepeatField),
+    AxisLock,
+    GuideLines,
+    Depth,
+    Back,
+}
+impl Row {
+    fn at(index: usize) -> Self {
+        const REPEATS: [(RepeatProfile, RepeatField); 6] = [
+            (RepeatProfile::Menu, RepeatField::Delay),
+            (RepeatProfile::Menu, RepeatField::Rate),
+            (RepeatProfile::Menu, RepeatField::Accel),
+            (RepeatProfile::Board, RepeatField::Delay),
+            (RepeatProfile::Board, RepeatField::Rate),
+            (RepeatProfile::Board, RepeatField::Accel),
+        ];
+        match index {
+            0 => Self::Preset,
+            i if i < LEFT_COLUMN_ROWS => Self::Binding(Action::ALL[i - 1]),
+            i if i < LEFT_COLUMN_ROWS + REPEATS.len() => {
+                let (profile, field) = REPEATS[i - LEFT_COLUMN_ROWS];
+                Self::Repeat(profile, field)
+            }
+            i if i == LEFT_COLUMN_ROWS + REPEATS.len() => Self::AxisLock,
+            i if i == LEFT_COLUMN_ROWS + REPEATS.len() + 1 => Self::GuideLines,
+            i if i == LEFT_COLUMN_ROWS + REPEATS.len() + 2 => Self::Depth,
+            _ => Self::Back,
+        }
+    }
+}
+
+/// Where a row's text lives in character memory, and how many tiles wide it is.
+fn row_layout(index: usize) -> (u16, u8) {
+    let left_chars = LEFT_ROW_TILES as u16 * 2;
+    let right_chars = RIGHT_ROW_TILES as u16 * 2;
+    match index {
+        _ if index == ROWS - 1 => (memory::SETTINGS_BACK_ROW.start, BACK_TILES),
+        0..SETTINGS_ROWS_BEFORE_PAUSE => (
+            memory::SETTINGS_LEFT_ROWS.start + index as u16 * left_chars,
+            LEFT_ROW_TILES,
+        ),
+        SETTINGS_ROWS_BEFORE_PAUSE..LEFT_COLUMN_ROWS => (
+            memory::SETTINGS_MORE_LEFT_ROWS.start
+                + (index - SETTINGS_ROWS_BEFORE_PAUSE) as u16 * left_chars,
+            LEFT_ROW_TILES,
+        ),
+        _ => (
+            memory::SETTINGS_RIGHT_ROWS.start + (index - LEFT_COLUMN_ROWS) as u16 * right_chars,
+            RIGHT_ROW_TILES,
+        ),
+    }
+}
+
+fn adjust(repeat: &mut KeyRepeat, field: RepeatField, delta: i8, wrap: bool) {
+    let (value, min, max) = match field {
+        RepeatField::Delay => (&mut repeat.delay, 2, KeyRepeat::MAX_DELAY),
+        RepeatField::Rate => (&mut repeat.interval, 1, KeyRepeat::MAX_INTERVAL),
+        RepeatField::Accel => (&mut repeat.fast_interval, 0, repeat.interval - 1),
+    };
+    let new_value = value.saturating_add_signed(delta);
+    *value = if wrap && new_value > max {
+        min
+    } else {
+        new_value.clamp(min, max)
+    };
+    // speeding up has to actually be faster
+    repeat.fast_interval = repeat.fast_interval.min(repeat.interval - 1);
+}
+
+pub struct SettingsMenu {
+    index: usize,
+    capturing: bool,
+    rows: [TextRenderer; ROWS],
+}
+
+impl SettingsMenu {
+    pub fn new() -> Self {
+        Self {
+            index: 0,
+            capturing: false,
+            rows: core::array::from_fn(|index| {
+                let (char_offset, tiles) = row_layout(index);
+                TextRenderer::new(&assets::MENU, char_offset, (tiles, 2))
+            }),
+        }
+    }
+
+    fn apply(&self, state: &mut GameState, settings: Settings) {
+        settings.save();
+        state.set_settings(settings);
+    }
+
+    fn display_rows(&mut self, state: &GameState) {
+        let settings = state.settings();
+        let controls = &settings.controls;
+        for (index, text) in self.rows.iter_mut().enumerate() {
+            text.clear();
+            let _ = match Row::at(index) {
+                Row::Preset => {
+                    let name = controls.preset().map_or("Custom", |p| p.name());
+                    write!(text, "Preset: {}", name)
+                }
+                Row::Binding(action) if self.capturing && index == self.index => {
+                    write!(text, "{}: press...", action.name())
+                }
+                Row::Binding(action) => {
+                    write!(
+                        text,
+                        "{}: {}",
+                        action.name(),
+                        controls.button(action).name()
+                    )
+                }
+                Row::Repeat(profile, field) => {
+                    let screen = match profile {
+                        RepeatProfile::Menu => "Menu",
+                        RepeatProfile::Board => "Board",
+                    };
+                    let repeat = settings.repeat(profile);
+                    match field {
+                        RepeatField::Delay => write!(text, "{} delay: {}", screen, repeat.delay),
+                        RepeatField::Rate => write!(text, "{} rate: {}", screen, repeat.interval),
+                        RepeatField::Accel if repeat.fast_interval == 0 => {
+                            write!(text, "{} accel: Off", screen)
+                        }
+                        RepeatField::Accel => {
+                            write!(text, "{} accel: {}", screen, repeat.fast_interval)
+                        }
+                    }
+                }
+                Row::AxisLock => {
+                    let value = if settings.axis_lock { "On" } else { "Off" };
+                    write!(text, "Axis lock: {}", value)
+                }
+                Row::GuideLines => {
+                    let value = if settings.guide_lines { "On" } else { "Off" };
+                    write!(text, "Guides: {}", value)
+                }
+                Row::Depth => write!(text, "Depth: {}", settings.depth.name()),
+                Row::Back => write!(text, "Back"),
+            };
+        }
+    }
+}
+
+impl Screen for SettingsMenu {
+    fn init(&mut self, state: &GameState) {
+        self.index = 0;
+        self.capturing = false;
+        for (index, row) in self.rows.iter().enumerate() {
+            row.render_to_bgmap(BG, (0, index as u8 * 2));
+        }
+        self.display_rows(state);
+    }
+
+    fn draw(&self, _state: &GameState, worlds: &mut Worlds) {
+        let text_height = assets::MENU.line_height as i16;
+
+        for (index, text) in self.rows.iter().enumerate() {
+            let (x, row) = if index < LEFT_COLUMN_ROWS {
+                (8 + (index / COLUMN_ROWS) as i16 * 128, index % COLUMN_ROWS)
+            } else {
+                (264, index - LEFT_COLUMN_ROWS)
+            };
+            let y = 16 + row as i16 * text_height;
+            let parallax = if index == self.index { -4 } else { 0 };
+            let src = (0, index as i16 * 16);
+            worlds.bgmap(BG, (x, y), parallax, src, (text.width(), text_height));
+        }
+    }
+
+    fn update(&mut self, state: &mut GameState) -> Option<Event> {
+        if self.capturing {
+            let pressed = state.buttons_pressed();
+            if let Some(button) = Button::ALL.into_iter().find(|b| b.is_down(pressed)) {
+                if let Row::Binding(action) = Row::at(self.index) {
+                    let mut settings = *state.settings();
+                    settings.controls.bind(action, button);
+                    self.apply(state, settings);
+                }
+                self.capturing = false;
+                self.display_rows(state);
+            }
+            return None;
+        }
+
+        let held = state.actions_repeated(RepeatProfile::Menu);
+        if held.contains(Action::Up) {
+            self.index = self.index.checked_sub(1).unwrap_or(ROWS - 1);
+        }
+        if held.contains(Action::Down) {
+            self.index = (self.index + 1) % ROWS;
+        }
+        if let Row::Repeat(profile, field) = Row::at(self.index) {
+            let delta = match (held.contains(Action::Left), held.contains(Action::Right)) {
+                (true, false) => -1,
+                (false, true) => 1,
+                _ => 0,
+            };
+            if delta != 0 {
+                let mut settings = *state.settings();
+                adjust(settings.repeat_mut(profile), field, delta, false);
+                self.apply(state, settings);
+                self.display_rows(state);
+            }
+        }
+
+        let pressed = state.actions_pressed();
+        if pressed.contains(Action::Cross) {
+            return Some(Event::Close);
+        }
+        if pressed.contains(Action::Fill) {
+            let mut settings = *state.settings();
+            match Row::at(self.index) {
+                Row::Preset => {
+                    let preset = match settings.controls.preset() {
+                        Some(preset) => preset.next(),
+                        None => Preset::Default,
+                    };
+                    settings.controls = preset.controls();
+                    self.apply(state, settings);
+                }
+                Row::Binding(_) => {
+                    self.capturing = true;
+                }
+                Row::Repeat(profile, field) => {
+                    adjust(settings.repeat_mut(profile), field, 1, true);
+                    self.apply(state, settings);
+                }
+                Row::AxisLock => {
+                    settings.axis_lock = !settings.axis_lock;
+                    self.apply(state, settings);
+                }
+                Row::GuideLines => {
+                    settings.guide_lines = !settings.guide_lines;
+                    self.apply(state, settings);
+                }
+                Row::Depth => {
+                    settings.depth = settings.depth.next();
+                    self.apply(state, settings);
+                }
+                Row::Back => {
+                    return Some(Event::Close);
+                }
+            }
+            self.display_rows(state);
+        }
+        None
+    }
+}
@@ -2,66 +2,308 @@ use rand::{Rng, SeedableRng};
 use rand_xoshiro::Xoroshiro128PlusPlus;
 use vb_rt::sys::hardware;
 
-const DPAD_OFFSETS: [u16; 4] = [8, 9, 10, 11];
+use crate::{
+    controls::{Action, Actions, RepeatProfile},
+    replay::Recording,
+    save::Settings,
+};
+
+/// Everything about the controller that carries over from one frame to the next.
+#[derive(Clone, Copy)]
+pub struct InputSnapshot {
+    curr_pressed: u16,
+    prev_pressed: u16,
+    curr_held: [u32; Action::ALL.len()],
+}
+
+enum InputMode {
+    Live,
+    Recording,
+    Playback {
+        started: bool,
+        index: usize,
+        frames: u16,
+        /// How many frames of input have been played back so far.
+        played: u32,
+        player_settings: Settings,
+    },
+}
 
 pub struct GameState {
     curr_pressed: hardware::GamePadData,
     prev_pressed: hardware::GamePadData,
-    curr_held: [u32; DPAD_OFFSETS.len()],
+    live_pressed: hardware::GamePadData,
+    live_prev_pressed: hardware::GamePadData,
+    settings: Settings,
+    curr_actions: Actions,
+    prev_actions: Actions,
+    curr_held: [u32; Action::ALL.len()],
     rand: Xoroshiro128PlusPlus,
+    frame_count: u32,
+    mode: InputMode,
+    recording: Recording,
 }
 
 impl GameState {
-    pub fn new() -> Self {
+    pub fn new(seed: u64) -> Self {
         Self {
             curr_pressed: hardware::GamePadData::new(),
             prev_pressed: hardware::GamePadData::new(),
-            curr_held: [0; DPAD_OFFSETS.len()],
-            rand: Xoroshiro128PlusPlus::seed_from_u64(0),
+            live_pressed: hardware::GamePadData::new(),
+            live_prev_pressed: hardware::GamePadData::new(),
+            settings: Settings::default(),
+            curr_actions: Actions::default(),
+            prev_actions: Actions::default(),
+            curr_held: [0; Action::ALL.len()],
+            rand: Xoroshiro128PlusPlus::seed_from_u64(seed),
+            frame_count: 0,
+            mode: InputMode::Live,
+            recording: Recording::new(),
         }
     }
 
-    pub fn buttons_held(&self) -> hardware::GamePadData {
-        self.curr_pressed
-    }
-
     pub fn buttons_pressed(&self) -> hardware::GamePadData {
         hardware::GamePadData::from_bits(
             self.curr_pressed.into_bits() & !self.prev_pressed.into_bits(),
         )
     }
 
-    pub fn directions_held(&self) -> hardware::GamePadData {
-        let mut bits = 0;
-        for (counter, button_offset) in self.curr_held.iter().zip(DPAD_OFFSETS) {
-            if *counter == 1 || *counter >= 10 {
-                bits |= 1 << button_offset;
+    /// Buttons just pressed on the real controller, even during playback.
+    pub fn live_buttons_pressed(&self) -> hardware::GamePadData {
+        hardware::GamePadData::from_bits(
+            self.live_pressed.into_bits() & !self.live_prev_pressed.into_bits(),
+        )
+    }
+
+    pub fn actions_held(&self) -> Actions {
+        self.curr_actions
+    }
+
+    pub fn actions_pressed(&self) -> Actions {
+        self.curr_actions.without(self.prev_actions)
+    }
+
+    /// Actions which were just pressed, or have been held long enough to repeat.
+    pub fn actions_repeated(&self, profile: RepeatProfile) -> Actions {
+        let repeat = self.settings.repeat(profile);
+        let mut actions = Actions::default();
+        for (counter, action) in self.curr_held.iter().zip(Action::ALL) {
+            if repeat.fires(*counter) {
+                actions = actions.with(action);
             }
         }
+        actions
+    }
 
-        hardware::GamePadData::from_bits(bits)
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    pub fn set_settings(&mut self, settings: Settings) {
+        self.settings = settings;
+        // don't let a button that was already down fire its new action
+        self.prev_actions = self.settings.controls.actions(self.curr_pressed);
+        self.curr_actions = self.prev_actions;
     }
 
     pub fn update(&mut self) {
-        let pressed = hardware::read_controller();
+        self.read_controller();
+
+        if let InputMode::Playback { started: false, .. } = self.mode {
+            // the first frame of a playback is the one the recording started on
+            self.restore_start();
+            return;
+        }
+        let pressed = self.next_playback_input().unwrap_or(self.live_pressed);
+        self.advance(pressed);
+        if let InputMode::Recording = self.mode {
+            self.recording.push(pressed.into_bits());
+        }
+    }
+
+    /// Advance one frame as if the controller reported `pressed`, for scripted demos.
+    /// The real controller is still read for [Self::live_buttons_pressed].
+    pub fn update_scripted(&mut self, pressed: hardware::GamePadData) {
+        self.read_controller();
+        self.advance(pressed);
+    }
+
+    /// Starts recording the controller from this frame, with a fresh rng seed.
+    /// Does nothing while playing back a recording.
+    pub fn start_recording(&mut self) {
+        if let InputMode::Playback { .. } = self.mode {
+            return;
+        }
+        let seed = self.rand.random();
+        self.reseed(seed);
+        let start = self.snapshot();
+        self.recording.begin(seed, self.settings, start);
+        self.mode = InputMode::Recording;
+    }
+
+    pub fn stop_recording(&mut self) {
+        if let InputMode::Recording = self.mode {
+            self.mode = InputMode::Live;
+        }
+    }
+
+    /// Marks the recording as paused, so anything playing it back knows the board stopped here.
+    pub fn mark_paused(&mut self) {
+        if let InputMode::Recording = self.mode {
+            self.recording.mark_paused();
+        }
+    }
+
+    pub fn mark_resumed(&mut self) {
+        if let InputMode::Recording = self.mode {
+            self.recording.mark_resumed();
+        }
+    }
+
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    /// Replaces the controller with a recording, starting on the next update.
+    /// The recording's own settings are used until it ends.
+    pub fn start_playback(&mut self, recording: &Recording) {
+        if !recording.is_complete() {
+            return;
+        }
+        self.stop_playback();
+        self.recording.clone_from(recording);
+        self.mode = InputMode::Playback {
+            started: false,
+            index: 0,
+            frames: 0,
+            played: 0,
+            player_settings: self.settings,
+        };
+    }
+
+    pub fn restart_playback(&mut self) {
+        if let InputMode::Playback {
+            started,
+            index,
+            frames,
+            played,
+            ..
+        } = &mut self.mode
+        {
+            *started = false;
+            *index = 0;
+            *frames = 0;
+            *played = 0;
+        }
+    }
+
+    pub fn stop_playback(&mut self) {
+        if let InputMode::Playback {
+            player_settings, ..
+        } = self.mode
+        {
+            self.mode = InputMode::Live;
+            self.set_settings(player_settings);
+        }
+    }
+
+    pub fn is_playing_back(&self) -> bool {
+        matches!(self.mode, InputMode::Playback { .. })
+    }
+
+    /// Whether the recording being played back was in the pause menu on this frame.
+    pub fn playback_paused(&self) -> bool {
+        match self.mode {
+            InputMode::Playback { played, .. } => self.recording.is_paused(played),
+            _ => false,
+        }
+    }
+
+    /// Replaces the rng's state outright. Recording and playback use this to make runs repeatable.
+    pub fn reseed(&mut self, seed: u64) {
+        self.rand = Xoroshiro128PlusPlus::seed_from_u64(seed);
+    }
+
+    pub fn rand(&mut self) -> &mut impl Rng {
+        &mut self.rand
+    }
+
+    fn read_controller(&mut self) {
+        self.frame_count = self.frame_count.wrapping_add(1);
+        self.live_prev_pressed = self.live_pressed;
+        self.live_pressed = hardware::read_controller();
+    }
+
+    fn snapshot(&self) -> InputSnapshot {
+        InputSnapshot {
+            curr_pressed: self.curr_pressed.into_bits(),
+            prev_pressed: self.prev_pressed.into_bits(),
+            curr_held: self.curr_held,
+        }
+    }
+
+    fn restore_start(&mut self) {
+        let Some(&start) = self.recording.start() else {
+            return;
+        };
+        self.settings = *self.recording.settings();
+        self.reseed(self.recording.seed());
+        self.curr_pressed = hardware::GamePadData::from_bits(start.curr_pressed);
+        self.prev_pressed = hardware::GamePadData::from_bits(start.prev_pressed);
+        self.curr_actions = self.settings.controls.actions(self.curr_pressed);
+        self.prev_actions = self.settings.controls.actions(self.prev_pressed);
+        self.curr_held = start.curr_held;
+        if let InputMode::Playback { started, .. } = &mut self.mode {
+            *started = true;
+        }
+    }
+
+    fn next_playback_input(&mut self) -> Option<hardware::GamePadData> {
+        let InputMode::Playback {
+            index,
+            frames,
+            played,
+            ..
+        } = &mut self.mode
+        else {
+            return None;
+        };
+        let Some((bits, count)) = self.recording.input(*index) else {
+            self.stop_playback();
+            return None;
+        };
+        *played += 1;
+        *frames += 1;
+        if *frames == count {
+            *index += 1;
+            *frames = 0;
+        }
+        Some(hardware::GamePadData::from_bits(bits))
+    }
+
+    fn advance(&mut self, pressed: hardware::GamePadData) {
         self.prev_pressed = self.curr_pressed;
         self.curr_pressed = pressed;
         if self.prev_pressed.into_bits() != self.curr_pressed.into_bits() {
-            let _: u32 = self.rand.random();
+            if let InputMode::Live = self.mode {
+                // nobody can time their button presses to the exact frame, so that's good entropy
+                let timing = u64::from(self.frame_count).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+                let seed = self.rand.random::<u64>() ^ timing;
+                self.reseed(seed);
+            } else {
+                // recordings and playback have to see the same sequence, so don't mix in anything new
+                let _: u32 = self.rand.random();
+            }
         }
 
-        let raw_bits = pressed.into_bits();
-        for (counter, button_offset) in self.curr_held.iter_mut().zip(DPAD_OFFSETS) {
-            let down = (raw_bits >> button_offset) & 1 == 1;
-            if down {
+        self.prev_actions = self.curr_actions;
+        self.curr_actions = self.settings.controls.actions(pressed);
+        for (counter, action) in self.curr_held.iter_mut().zip(Action::ALL) {
+            if self.curr_actions.contains(action) {
                 *counter += 1;
             } else {
                 *counter = 0;
             }
         }
     }
-
-    pub fn rand(&mut self) -> &mut impl Rng {
-        &mut self.rand
-    }
 }
@@ -0,0 +1,212 @@
+use core::fmt::Write as _;
+
+use vb_graphics::text::TextRenderer;
+
+use crate::{
+    assets,
+    controls::Action,
+    memory,
+    puzzle::{ICONS, PUZZLES},
+    save::SaveData,
+    screen::{Event, Screen},
+    state::GameState,
+    worlds::Worlds,
+};
+
+const BG: u8 = memory::STATS_BG.index;
+/// Solved, total, average, fastest, slowest and mistakes, then the size classes.
+const SUMMARY_ROWS: usize = 6;
+pub const ROWS: usize = SUMMARY_ROWS + SIZE_CLASSES.len() / CLASSES_PER_ROW;
+pub const ROW_TILES: u8 = 14;
+/// Puzzles are grouped by their longest side.
+const SIZE_CLASSES: [usize; 4] = [5, 10, 15, 20];
+/// The size classes are short, so two share a row, which leaves room for the rest in three columns.
+const CLASSES_PER_ROW: usize = 2;
+/// The rows of text are laid out in columns of this many, across the top of the screen.
+const ROWS_PER_COLUMN: usize = 3;
+const COLUMN_WIDTH: i16 = 124;
+const MOSAIC_COLUMNS: usize = 9;
+const MOSAIC_ROWS: usize = 4;
+const _: () = assert!(
+    PUZZLES.len() <= MOSAIC_COLUMNS * MOSAIC_ROWS,
+    "the mosaic has no room for every puzzle"
+);
+/// Where the mosaic starts in the background map, in cells, off to the right of the text.
+const MOSAIC_CELL_X: u8 = 16;
+/// Each icon is 5 cells square.
+const ICON_SIZE: i16 = 40;
+
+/// Everything worth knowing about how the player's done, worked out from their save.
+#[derive(Default)]
+struct Statistics {
+    solved: usize,
+    total_time: u32,
+    /// The index and time of the fastest clear.
+    fastest: Option<(usize, u32)>,
+    slowest: Option<(usize, u32)>,
+    /// How many mistakes the solved puzzles' best runs made between them.
+    mistakes: u32,
+    /// How many puzzles there are to solve. The bonus puzzles only count once they're unlocked.
+    available: usize,
+    /// How many puzzles of each size class are solved, out of how many there are.
+    sizes: [(usize, usize); SIZE_CLASSES.len()],
+}
+
+impl Statistics {
+    fn new(saved: &SaveData) -> Self {
+        let mut stats = Self {
+            available: saved.available_puzzles(),
+            ..Self::default()
+        };
+        let puzzles = PUZZLES.iter().zip(saved.times).take(stats.available);
+        for (index, (puzzle, time)) in puzzles.enumerate() {
+            let size = puzzle.width.max(puzzle.height);
+            let class = SIZE_CLASSES.iter().position(|&s| s >= size);
+            let class = &mut stats.sizes[class.unwrap_or(SIZE_CLASSES.len() - 1)];
+            class.1 += 1;
+            let Some(time) = time else {
+                continue;
+            };
+            class.0 += 1;
+            stats.solved += 1;
+            stats.total_time = stats.total_time.saturating_add(time);
+            stats.mistakes += saved.mistakes[index] as u32;
+            if stats.fastest.is_none_or(|(_, fastest)| time < fastest) {
+                stats.fastest = Some((index, time));
+            }
+            if stats.slowest.is_none_or(|(_, slowest)| time > slowest) {
+                stats.slowest = Some((index, time));
+            }
+        }
+        stats
+    }
+
+    fn average_time(&self) -> Option<u32> {
+        (self.solved > 0).then(|| self.total_time / self.solved as u32)
+    }
+}
+
+/// Where a row's text lives in character memory.
+fn row_offset(index: usize) -> u16 {
+    memory::STATS_ROWS.start + index as u16 * ROW_TILES as u16 * 2
+}
+
+/// Writes a time in frames as minutes and seconds, with hours only once there are some.
+pub fn write_time(text: &mut TextRenderer, time: Option<u32>) -> core::fmt::Result {
+    let Some(time) = time else {
+        return write!(text, "-");
+    };
+    let seconds = time / 50;
+    let (hours, minutes, seconds) = (seconds / 60 / 60, (seconds / 60) % 60, seconds % 60);
+    if hours > 0 {
+        write!(text, "{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        write!(text, "{minutes}:{seconds:02}")
+    }
+}
+
+/// Shows how far the player's gotten through every puzzle, above a mosaic of the ones they've solved.
+pub struct StatsScreen {
+    stats: Statistics,
+    rows: [TextRenderer; ROWS],
+}
+
+impl StatsScreen {
+    pub fn new() -> Self {
+        Self {
+            stats: Statistics::default(),
+            rows: core::array::from_fn(|index| {
+                TextRenderer::new(&assets::MENU, row_offset(index), (ROW_TILES, 2))
+            }),
+        }
+    }
+
+    /// Works out the statistics to show next time the screen opens, and lays out the mosaic.
+    /// Every solved puzzle's icon goes in, in order, with no gaps for the unsolved ones.
+    pub fn load(&mut self, saved: &SaveData) {
+        self.stats = Statistics::new(saved);
+        let solved = (0..self.stats.available).filter(|&index| saved.times[index].is_some());
+        for (position, index) in solved.enumerate() {
+            let col = (position % MOSAIC_COLUMNS) as u8;
+            let row = (position / MOSAIC_COLUMNS) as u8;
+            ICONS[index].render_to_bgmap(BG, (MOSAIC_CELL_X + col * 5, row * 5));
+        }
+    }
+
+    fn display_rows(&mut self) {
+        let stats = &self.stats;
+        for (index, text) in self.rows.iter_mut().enumerate() {
+            text.clear();
+            let _ = match index {
+                0 => write!(text, "Solved {}/{}", stats.solved, stats.available),
+                1 => write!(text, "Total ").and_then(|_| write_time(text, Some(stats.total_time))),
+                2 => write!(text, "Average ").and_then(|_| write_time(text, stats.average_time())),
+                3 | 4 => {
+                    let (label, clear) = if index == 3 {
+                        ("Fastest", stats.fastest)
+                    } else {
+                        ("Slowest", stats.slowest)
+                    };
+                    match clear {
+                        Some((puzzle, time)) => write!(text, "{label} #{} ", puzzle + 1)
+                            .and_then(|_| write_time(text, Some(time))),
+                        None => write!(text, "{label} -"),
+                    }
+                }
+                5 => write!(text, "Mistakes {}", stats.mistakes),
+                _ => {
+                    let first = (index - SUMMARY_ROWS) * CLASSES_PER_ROW;
+                    (first..first + CLASSES_PER_ROW).try_for_each(|class| {
+                        let size = SIZE_CLASSES[class];
+                        let (solved, total) = stats.sizes[class];
+                        let gap = if class == first { "" } else { " " };
+                        write!(text, "{gap}{size}x{size} {solved}/{total}")
+                    })
+                }
+            };
+        }
+    }
+}
+
+impl Screen for StatsScreen {
+    fn init(&mut self, _state: &GameState) {
+        for (index, row) in self.rows.iter().enumerate() {
+            row.render_to_bgmap(BG, (0, index as u8 * 2));
+        }
+        self.display_rows();
+    }
+
+    fn draw(&self, _state: &GameState, worlds: &mut Worlds) {
+        let text_height = assets::MENU.line_height as i16;
+        for (index, text) in self.rows.iter().enumerate() {
+            let (col, row) = (index / ROWS_PER_COLUMN, index % ROWS_PER_COLUMN);
+            let pos = (12 + col as i16 * COLUMN_WIDTH, 8 + row as i16 * text_height);
+            let src = (0, index as i16 * 16);
+            worlds.bgmap(BG, pos, 0, src, (text.width(), text_height));
+        }
+
+        // the full rows of the mosaic go in one world, and whatever's left over in another
+        let mosaic_x = MOSAIC_CELL_X as i16 * 8;
+        let full_rows = (self.stats.solved / MOSAIC_COLUMNS) as i16;
+        let leftover = (self.stats.solved % MOSAIC_COLUMNS) as i16;
+        let width = MOSAIC_COLUMNS as i16 * ICON_SIZE;
+        let (x, y) = (12, 8 + ROWS_PER_COLUMN as i16 * text_height + 4);
+        if full_rows > 0 {
+            let size = (width, full_rows * ICON_SIZE);
+            worlds.bgmap(BG, (x, y), 0, (mosaic_x, 0), size);
+        }
+        if leftover > 0 {
+            let top = full_rows * ICON_SIZE;
+            let size = (leftover * ICON_SIZE, ICON_SIZE);
+            worlds.bgmap(BG, (x, y + top), 0, (mosaic_x, top), size);
+        }
+    }
+
+    fn update(&mut self, state: &mut GameState) -> Option<Event> {
+        let pressed = state.actions_pressed();
+        (pressed.contains(Action::Fill)
+            || pressed.contains(Action::Cross)
+            || pressed.contains(Action::Pause))
+        .then_some(Event::Close)
+    }
+}
@@ -1,10 +1,17 @@
-use vb_rt::sys::vip;
+use crate::{
+    assets, memory,
+    screen::{Charset, Event, Screen},
+    state::GameState,
+    worlds::{Eyes, Worlds},
+};
 
-use crate::{assets, state::GameState};
+const BG: u8 = memory::TITLE_BG.index;
+/// How long the title waits for START before showing a demo, about 30 seconds.
+const IDLE_FRAMES: u16 = 1500;
 
-const BG: u8 = 4;
 pub struct Title {
     timer: u8,
+    idle: u16,
 }
 
 impl Title {
@@ -12,75 +19,38 @@ impl Title {
         assets::TITLE_LEFT.render_to_bgmap(BG, (0, 0));
         assets::TITLE_RIGHT.render_to_bgmap(BG, (0, 28));
         assets::START.render_to_bgmap(BG, (0, 56));
-        Self { timer: 0 }
+        Self { timer: 0, idle: 0 }
     }
+}
 
-    pub fn draw(&self) {
-        let mut next_world = 31;
-
-        let world = vip::WORLDS.index(next_world);
-        next_world -= 1;
-        world.header().write(
-            vip::WorldHeader::new()
-                .with_bgm(vip::WorldMode::Normal)
-                .with_lon(true)
-                .with_ron(false)
-                .with_bg_map_base(BG),
-        );
-        world.gx().write(0);
-        world.gp().write(0);
-        world.gy().write(0);
-        world.mx().write(0);
-        world.my().write(0);
-        world.w().write(383);
-        world.h().write(223);
+impl Screen for Title {
+    fn charset(&self) -> Charset {
+        Charset::Title
+    }
 
-        let world = vip::WORLDS.index(next_world);
-        next_world -= 1;
-        world.header().write(
-            vip::WorldHeader::new()
-                .with_bgm(vip::WorldMode::Normal)
-                .with_lon(false)
-                .with_ron(true)
-                .with_bg_map_base(BG),
-        );
-        world.gx().write(0);
-        world.gp().write(0);
-        world.gy().write(0);
-        world.mx().write(0);
-        world.my().write(224);
-        world.w().write(383);
-        world.h().write(223);
+    fn init(&mut self, _state: &GameState) {
+        self.timer = 0;
+        self.idle = 0;
+    }
 
+    fn draw(&self, _state: &GameState, worlds: &mut Worlds) {
+        worlds.bgmap_for(Eyes::Left, BG, (0, 0), 0, (0, 0), (384, 224));
+        worlds.bgmap_for(Eyes::Right, BG, (0, 0), 0, (0, 224), (384, 224));
         if self.timer / 32 == 0 {
-            let world = vip::WORLDS.index(next_world);
-            next_world -= 1;
-            world.header().write(
-                vip::WorldHeader::new()
-                    .with_bgm(vip::WorldMode::Normal)
-                    .with_lon(true)
-                    .with_ron(true)
-                    .with_bg_map_base(BG),
-            );
-            world.gx().write(128);
-            world.gp().write(-4);
-            world.gy().write(168);
-            world.mx().write(0);
-            world.my().write(448);
-            world.w().write(127);
-            world.h().write(31);
+            worlds.bgmap(BG, (128, 168), -4, (0, 448), (128, 32));
         }
-
-        let world = vip::WORLDS.index(next_world);
-        world.header().write(vip::WorldHeader::new().with_end(true));
     }
 
-    pub fn update(&mut self, state: &GameState) -> bool {
+    fn update(&mut self, state: &mut GameState) -> Option<Event> {
         let pressed = state.buttons_pressed();
         if pressed.sta() {
-            return true;
+            return Some(Event::Start);
+        }
+        if pressed.into_bits() != 0 {
+            self.idle = 0;
         }
         self.timer = (self.timer + 1) % 64;
-        false
+        self.idle += 1;
+        (self.idle == IDLE_FRAMES).then_some(Event::Demo)
     }
 }
@@ -0,0 +1,163 @@
+use vb_graphics::{self as gfx, Image};
+use vb_rt::sys::vip;
+
+use crate::{memory, worlds::Worlds};
+
+const BG: u8 = memory::COVER_BG.index;
+const COVER_CHAR: u16 = memory::COVER.start;
+/// The cover is drawn with its own palette, which maps every color to black.
+const COVER_PALETTE: u8 = 3;
+const COVER_CELLS: [vip::Cell; 48 * 28] = [vip::Cell::new()
+    .with_character(COVER_CHAR)
+    .with_palette(COVER_PALETTE); 48 * 28];
+const COVER: Image = Image {
+    width_cells: 48,
+    height_cells: 28,
+    data: &COVER_CELLS,
+};
+/// How far back the zoom transition pushes the whole screen.
+const ZOOM_PARALLAX: i32 = 24;
+/// Progress through a transition is measured out of this much.
+pub const FULL: u32 = 256;
+
+pub const FADE: Transition = Transition::new(Style::Fade, 32, Easing::Linear);
+pub const WIPE: Transition = Transition::new(Style::Wipe, 24, Easing::InOut);
+pub const IRIS: Transition = Transition::new(Style::Iris, 28, Easing::In);
+pub const ZOOM: Transition = Transition::new(Style::Zoom, 32, Easing::Out);
+
+/// Sets up the black cover which the wipe and iris transitions draw over the screen.
+pub fn init() {
+    gfx::load_character_data(&[vip::Character([0xffff; 8])], COVER_CHAR as usize);
+    vip::GPLT3.write(0);
+    COVER.render_to_bgmap(BG, (0, 0));
+}
+
+#[derive(Clone, Copy)]
+pub enum Style {
+    Fade,
+    /// A black curtain sweeping across from left to right.
+    Wipe,
+    /// A black frame closing in on the middle of the screen, and opening back out.
+    Iris,
+    /// Pushes the screen back into the distance as it fades.
+    Zoom,
+}
+
+#[derive(Clone, Copy)]
+pub enum Easing {
+    Linear,
+    In,
+    Out,
+    InOut,
+}
+impl Easing {
+    /// Eases progress out of [FULL].
+    pub fn apply(self, progress: u32) -> u32 {
+        let rest = FULL - progress;
+        match self {
+            Self::Linear => progress,
+            Self::In => progress * progress / FULL,
+            Self::Out => FULL - rest * rest / FULL,
+            Self::InOut if progress < FULL / 2 => 2 * progress * progress / FULL,
+            Self::InOut => FULL - 2 * rest * rest / FULL,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    /// Covering up the old screen.
+    Out,
+    /// Uncovering the new one.
+    In,
+}
+
+/// Hides one screen and reveals the next. The screen is fully covered between the two halves,
+/// so whatever gets loaded in between never shows up half-drawn.
+#[derive(Clone, Copy)]
+pub struct Transition {
+    style: Style,
+    easing: Easing,
+    duration: u16,
+    frame: u16,
+    direction: Direction,
+}
+
+impl Transition {
+    pub const fn new(style: Style, duration: u16, easing: Easing) -> Self {
+        Self {
+            style,
+            easing,
+            duration,
+            frame: 0,
+            direction: Direction::Out,
+        }
+    }
+
+    /// The second half of this transition, which reveals the new screen.
+    pub fn entering(self) -> Self {
+        Self {
+            frame: 0,
+            direction: Direction::In,
+            ..self
+        }
+    }
+
+    /// Moves the transition along a frame. Returns true once it's finished.
+    pub fn update(&mut self) -> bool {
+        self.frame += 1;
+        self.frame > self.duration
+    }
+
+    /// How much of the screen is hidden, out of [FULL].
+    fn coverage(&self) -> u32 {
+        let progress = (self.frame.min(self.duration) as u32 * FULL) / self.duration.max(1) as u32;
+        let eased = self.easing.apply(progress);
+        match self.direction {
+            Direction::Out => eased,
+            Direction::In => FULL - eased,
+        }
+    }
+
+    /// Draws the transition over whatever the screens have drawn so far.
+    pub fn draw(&self, worlds: &mut Worlds) {
+        let coverage = self.coverage();
+        let brightness = match self.style {
+            Style::Fade | Style::Zoom => (32 * (FULL - coverage) / FULL) as u8,
+            Style::Wipe | Style::Iris => 32,
+        };
+        gfx::set_colors(brightness, brightness * 2, brightness);
+
+        match self.style {
+            Style::Fade => {}
+            Style::Zoom => {
+                worlds.shift_parallax((ZOOM_PARALLAX * coverage as i32 / FULL as i32) as i16);
+            }
+            Style::Wipe => {
+                let width = (384 * coverage / FULL) as i16;
+                let left = match self.direction {
+                    Direction::Out => 0,
+                    Direction::In => 384 - width,
+                };
+                draw_cover((left, 0), (width, 224), worlds);
+            }
+            Style::Iris => {
+                let half_width = (192 * (FULL - coverage) / FULL) as i16;
+                let half_height = (112 * (FULL - coverage) / FULL) as i16;
+                let (left, right) = (192 - half_width, 192 + half_width);
+                let (top, bottom) = (112 - half_height, 112 + half_height);
+                draw_cover((0, 0), (384, top), worlds);
+                draw_cover((0, bottom), (384, 224 - bottom), worlds);
+                draw_cover((0, top), (left, bottom - top), worlds);
+                draw_cover((right, top), (384 - right, bottom - top), worlds);
+            }
+        }
+    }
+}
+
+/// Draws a black box over everything drawn so far.
+pub fn draw_cover(pos: (i16, i16), (width, height): (i16, i16), worlds: &mut Worlds) {
+    if width > 0 && height > 0 {
+        worlds.bgmap(BG, pos, 0, (0, 0), (width, height));
+    }
+}
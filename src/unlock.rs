@@ -0,0 +1,99 @@
+use core::fmt::Write as _;
+
+use vb_graphics::text::TextRenderer;
+
+use crate::{
+    assets,
+    controls::Action,
+    game::megu::Megu,
+    memory,
+    puzzle::{BONUS_PUZZLES, BONUS_START},
+    screen::{Event, Screen},
+    state::GameState,
+    worlds::Worlds,
+};
+
+/// Megu's poses only take up the left of her background map, so the text goes off to the right.
+const BG: u8 = memory::MEGU_BG.index;
+const TEXT_CELL_X: u8 = 32;
+/// How long Megu waits before she unlocks the bonus puzzles, in frames.
+const REVEAL_FRAMES: u16 = 60;
+
+/// Celebrates with Megu when every main puzzle is solved, and the bonus puzzles unlock.
+pub struct UnlockScreen {
+    megu: Megu,
+    frame: u16,
+    headline_text: TextRenderer,
+    detail_text: TextRenderer,
+}
+
+impl UnlockScreen {
+    pub fn new() -> Self {
+        let headline_text = memory::UNLOCK_HEADLINE_TEXT.text_renderer();
+        headline_text.render_to_bgmap(BG, (TEXT_CELL_X, 0));
+        let detail_text = memory::UNLOCK_DETAIL_TEXT.text_renderer();
+        detail_text.render_to_bgmap(BG, (TEXT_CELL_X, 2));
+        Self {
+            megu: Megu::new(),
+            frame: 0,
+            headline_text,
+            detail_text,
+        }
+    }
+
+    fn revealed(&self) -> bool {
+        self.frame >= REVEAL_FRAMES
+    }
+
+    fn reveal(&mut self) {
+        self.megu.win();
+        self.headline_text.clear();
+        self.headline_text.draw_text(b"Bonus puzzles unlocked!");
+        self.detail_text.clear();
+        let _ = write!(
+            &mut self.detail_text,
+            "{} more in the menu",
+            BONUS_PUZZLES.len()
+        );
+    }
+}
+
+impl Screen for UnlockScreen {
+    fn init(&mut self, _state: &GameState) {
+        self.megu.init((192, 128));
+        self.frame = 0;
+        // other screens borrow these characters while this one's closed
+        self.headline_text.clear();
+        let _ = write!(&mut self.headline_text, "All {BONUS_START} puzzles solved!");
+        self.detail_text.clear();
+    }
+
+    fn draw(&self, _state: &GameState, worlds: &mut Worlds) {
+        self.megu.draw(worlds);
+        let text_height = assets::MENU.line_height as i16;
+        let x = TEXT_CELL_X as i16 * 8;
+        let width = self.headline_text.width();
+        worlds.bgmap(BG, (192 - width / 2, 24), 0, (x, 0), (width, text_height));
+        if !self.detail_text.is_empty() {
+            let width = self.detail_text.width();
+            let pos = (192 - width / 2, 24 + text_height);
+            worlds.bgmap(BG, pos, 0, (x, 16), (width, text_height));
+        }
+    }
+
+    fn update(&mut self, state: &mut GameState) -> Option<Event> {
+        self.megu.update(state);
+        if !self.revealed() {
+            self.frame += 1;
+            if self.revealed() {
+                self.reveal();
+            }
+            return None;
+        }
+        let pressed = state.actions_pressed();
+        (pressed.contains(Action::Fill)
+            || pressed.contains(Action::Cross)
+            || pressed.contains(Action::Pause))
+        .then_some(Event::Close)
+    }
+}
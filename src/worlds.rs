@@ -0,0 +1,182 @@
+use vb_rt::sys::vip;
+
+use crate::memory;
+
+const WORLD_COUNT: usize = 32;
+/// Where the background maps start, which is what the world parameter tables are measured from.
+const BG_MAP_ADDRESS: usize = 0x0002_0000;
+
+/// Which eyes can see a world.
+#[derive(Clone, Copy)]
+pub enum Eyes {
+    Both,
+    Left,
+    Right,
+}
+
+/// Hands out the VIP's worlds for one frame, from the back of the screen to the front.
+/// Whatever's drawn first ends up behind everything drawn after it.
+pub struct Worlds {
+    /// How many worlds are still free. The next one handed out is the one just below this.
+    free: usize,
+    /// How many draws didn't get a world, because they'd all been handed out already.
+    dropped: usize,
+    /// How many lines of the parameter tables have been handed out.
+    param_lines: u16,
+}
+
+impl Worlds {
+    pub fn new() -> Self {
+        Self {
+            free: WORLD_COUNT,
+            dropped: 0,
+            param_lines: 0,
+        }
+    }
+
+    fn claim(&mut self, header: vip::WorldHeader) -> Option<usize> {
+        if self.free == 0 {
+            self.dropped += 1;
+            return None;
+        }
+        self.free -= 1;
+        vip::WORLDS.index(self.free).header().write(header);
+        Some(self.free)
+    }
+
+    /// Shows a `width` by `height` pixel window of a background map, starting from `(mx, my)`.
+    pub fn bgmap(
+        &mut self,
+        bg: u8,
+        pos: (i16, i16),
+        parallax: i16,
+        src: (i16, i16),
+        size: (i16, i16),
+    ) {
+        self.bgmap_for(Eyes::Both, bg, pos, parallax, src, size);
+    }
+
+    /// Like [Self::bgmap], but only one eye might see it.
+    pub fn bgmap_for(
+        &mut self,
+        eyes: Eyes,
+        bg: u8,
+        (x, y): (i16, i16),
+        parallax: i16,
+        (mx, my): (i16, i16),
+        (width, height): (i16, i16),
+    ) {
+        let header = vip::WorldHeader::new()
+            .with_bgm(vip::WorldMode::Normal)
+            .with_lon(!matches!(eyes, Eyes::Right))
+            .with_ron(!matches!(eyes, Eyes::Left))
+            .with_bg_map_base(bg);
+        let Some(index) = self.claim(header) else {
+            return;
+        };
+        let world = vip::WORLDS.index(index);
+        world.gx().write(x);
+        world.gp().write(parallax);
+        world.gy().write(y);
+        world.mx().write(mx);
+        world.my().write(my);
+        world.w().write(width - 1);
+        world.h().write(height - 1);
+    }
+
+    /// Shows the same window of several background maps, each at its own parallax, magnified by
+    /// `scale.0 / scale.1`. The maps share one parameter table, so they all stretch the same way.
+    pub fn bgmaps_scaled(
+        &mut self,
+        layers: &[(u8, i16)],
+        (x, y): (i16, i16),
+        (mx, my): (i16, i16),
+        (width, height): (i16, i16),
+        (num, den): (i16, i16),
+    ) {
+        if num == den {
+            for &(bg, parallax) in layers {
+                self.bgmap(bg, (x, y), parallax, (mx, my), (width, height));
+            }
+            return;
+        }
+        let first_line = self.param_lines;
+        if first_line + height as u16 > memory::PARAM_LINES {
+            self.dropped += layers.len();
+            return;
+        }
+        self.param_lines += height as u16;
+        // the source is 13.3 fixed point, and the step across each line is 7.9
+        let (num, den) = (num as i32, den as i32);
+        let dx = (512 * den / num) as i16;
+        for line in 0..height {
+            let line_my = (my as i32 * 8 + line as i32 * 8 * den / num) as i16;
+            write_params(
+                first_line + line as u16,
+                [mx * 8, 0, line_my, dx, 0, 0, 0, 0],
+            );
+        }
+        for &(bg, parallax) in layers {
+            let header = vip::WorldHeader::new()
+                .with_bgm(vip::WorldMode::Affine)
+                .with_lon(true)
+                .with_ron(true)
+                .with_bg_map_base(bg);
+            let Some(index) = self.claim(header) else {
+                return;
+            };
+            let world = vip::WORLDS.index(index);
+            world.gx().write(x);
+            world.gp().write(parallax);
+            world.gy().write(y);
+            world.w().write(width - 1);
+            world.h().write(height - 1);
+            world
+                .param_base()
+                .write(memory::PARAM_TABLES + first_line * 8);
+        }
+    }
+
+    /// Shows the next group of objects, the first group in SPT3, the next in SPT2 and so on.
+    pub fn objects(&mut self) {
+        let header = vip::WorldHeader::new()
+            .with_bgm(vip::WorldMode::Object)
+            .with_lon(true)
+            .with_ron(true);
+        self.claim(header);
+    }
+
+    /// Pushes every world handed out so far further into (or out of) the screen.
+    pub fn shift_parallax(&self, offset: i16) {
+        for index in self.free..WORLD_COUNT {
+            let gp = vip::WORLDS.index(index).gp();
+            gp.write(gp.read() + offset);
+        }
+    }
+
+    /// Ends the frame's worlds, so the VIP skips the ones nothing was drawn into. Returns how many
+    /// draws were dropped because there were no worlds or parameter table lines left for them.
+    pub fn finish(self) -> usize {
+        debug_assert!(
+            self.dropped == 0,
+            "ran out of worlds, {} draws were dropped",
+            self.dropped
+        );
+        if self.free > 0 {
+            let header = vip::WorldHeader::new().with_end(true);
+            vip::WORLDS.index(self.free - 1).header().write(header);
+        }
+        self.dropped
+    }
+}
+
+/// Writes one line of an affine world's parameter table.
+fn write_params(line: u16, params: [i16; 8]) {
+    let offset = (memory::PARAM_TABLES as usize + line as usize * 8) * 2;
+    let entry = (BG_MAP_ADDRESS + offset) as *mut i16;
+    for (index, param) in params.into_iter().enumerate() {
+        // SAFETY: the parameter tables live in VIP memory past the last background map,
+        // which nothing else uses, and `line` was checked against their size.
+        unsafe { entry.add(index).write_volatile(param) };
+    }
+}